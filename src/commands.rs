@@ -0,0 +1,1060 @@
+// Author: Grant Duchars
+use registrar::dbmgmt::*;
+use registrar::error::{RegistrarError, Result};
+use registrar::migrations::{self, migrate_down, migrate_up};
+use registrar::output::{render, Format};
+use registrar::store::{DeletePolicy, Referenced, RegistrarStore};
+use registrar::transcript::{self, GpaRule, RepeatPolicy, TextRenderer, Transcript, TranscriptRenderer};
+use rust_decimal::Decimal;
+use time::Date;
+
+// Start insert functions
+/// Function to insert items into the database. Possible items to insert are courses, grades, semesters, students, and taken courses.
+pub fn insert_into_database(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<String> {
+    let item = match input.get(1) {
+        Some(&"c") => insert_course(
+            store,
+            Course {
+                prefix: match input.get(2) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Course needs a prefix.",
+                    )))
+                },
+                number: match input.get(3) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Course needs a number.",
+                    )))
+                },
+                title: match input.get(4) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Course needs a title.",
+                    )))
+                },
+                credits: match input.get(5) {
+                    Some(item) => parse_credits(item, "Unable to insert item")?,
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Course needs number of credits.",
+                    )))
+                },
+            },
+        )?,
+        Some(&"g") => {
+            // Check the flags after the value are ones a grade can have
+            if let Some(flag) = input.iter().skip(4).find(|item| !GRADE_FLAGS.contains(item)) {
+                return Err(RegistrarError::Validation(format!(
+                    "Invalid flag '{flag}'. Valid flags are --no-gpa, --credit, --no-credit, and --not-attempted.",
+                )));
+            }
+            if input.contains(&"--credit") && input.contains(&"--no-credit") {
+                return Err(RegistrarError::Validation(String::from(
+                    "Unable to insert item. Grade cannot be given both --credit and --no-credit.",
+                )));
+            }
+            let value = match input.get(3) {
+                Some(item) => parse_grade_value(item, "Unable to insert item")?,
+                None => return Err(RegistrarError::Validation(String::from(
+                    "Unable to insert item. Grade needs a point value.",
+                )))
+            };
+            insert_grade(
+                store,
+                Grade {
+                    letter: match input.get(2) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Grade needs a type.",
+                        )))
+                    },
+                    value,
+                    // Grades count toward everything unless flags after the value say otherwise, except
+                    // that a zero point grade like F earns no credit the same way migration 6 set up
+                    counts_toward_gpa: !input.contains(&"--no-gpa"),
+                    earns_credit: input.contains(&"--credit")
+                        || (earns_credit_by_default(value) && !input.contains(&"--no-credit")),
+                    attempted: !input.contains(&"--not-attempted"),
+                },
+            )?
+        }
+        Some(&"m") => insert_semester(
+            store,
+            Semester {
+                code: match input.get(2) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Semester needs a code.",
+                    )))
+                },
+                year: match input.get(3) {
+                    Some(item) => parse_year(item, "Unable to insert item")?,
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Semester needs a year.",
+                    )))
+                },
+                description: match input.get(4) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Semester needs a description.",
+                    )))
+                },
+                // The start and end dates are optional
+                start_date: match input.get(5) {
+                    Some(item) => Some(parse_date(item, "Unable to insert item")?),
+                    None => None,
+                },
+                end_date: match input.get(6) {
+                    Some(item) => Some(parse_date(item, "Unable to insert item")?),
+                    None => None,
+                },
+            },
+        )?,
+        Some(&"r") => insert_term(
+            store,
+            Term {
+                name: match input.get(2) {
+                    Some(item) => item.to_string(),
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Term needs a name.",
+                    )))
+                },
+                sequence: match input.get(3) {
+                    Some(item) => parse_sequence(item, "Unable to insert item")?,
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to insert item. Term needs a sequence number.",
+                    )))
+                },
+            },
+        )?,
+        Some(&"s") => {
+            // Students can be given an ID as #<id>, otherwise the database picks one
+            let (id, offset) = match input.get(2).and_then(|item| item.strip_prefix('#')) {
+                Some(item) => (parse_student_id(item, "Unable to insert item")?, 1),
+                None => (0, 0),
+            };
+            insert_student(
+                store,
+                Student {
+                    id,
+                    lname: match input.get(2 + offset) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Student needs a last name.",
+                        )))
+                    },
+                    fname: match input.get(3 + offset) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Student needs a first name.",
+                        )))
+                    },
+                    phone: match input.get(4 + offset) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Student needs a phone number.",
+                        )))
+                    },
+                },
+            )?
+        }
+        Some(&"t") => {
+            // The student is given by ID or by name, which takes up a different number of items
+            let (student, next) = find_student(store, &input, 2, "Unable to insert item")?;
+            insert_taken_course(
+                store,
+                TakenCourse {
+                    student_id: student.id,
+                    course_prefix: match input.get(next) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Taken course needs a course's prefix.",
+                        )))
+                    },
+                    course_number: match input.get(next + 1) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Taken course needs a course's number.",
+                        )))
+                    },
+                    grade_letter: match input.get(next + 2) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Taken course needs a grade type.",
+                        )))
+                    },
+                    semester_code: match input.get(next + 3) {
+                        Some(item) => item.to_string(),
+                        None => return Err(RegistrarError::Validation(String::from(
+                            "Unable to insert item. Taken course needs a semester code.",
+                        )))
+                    },
+                },
+            )?
+        }
+        _ => return Err(RegistrarError::Validation(String::from(
+            "Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ester, te(r)m, (s)tudent, and (t)aken course.",
+        ))),
+    };
+    Ok(item)
+}
+
+fn insert_course(store: &mut dyn RegistrarStore, course: Course) -> Result<String> {
+    let course = add_course(store, course)?;
+    // Send the OK! that the item was inserted
+    Ok(format_course(&course))
+}
+
+fn insert_grade(store: &mut dyn RegistrarStore, grade: Grade) -> Result<String> {
+    let grade = add_grade(store, grade)?;
+    // Send the OK! that the item was inserted
+    Ok(format_grade(&grade))
+}
+
+fn insert_semester(store: &mut dyn RegistrarStore, semester: Semester) -> Result<String> {
+    let semester = add_semester(store, semester)?;
+    // Send the OK! that the item was inserted
+    Ok(format_semester(&semester))
+}
+
+fn insert_term(store: &mut dyn RegistrarStore, term: Term) -> Result<String> {
+    let term = add_term(store, term)?;
+    // Send the OK! that the item was inserted
+    Ok(format!("{} {}", term.name, term.sequence))
+}
+
+fn insert_student(store: &mut dyn RegistrarStore, student: Student) -> Result<String> {
+    let student = add_student(store, student)?;
+    // Send the OK! that the item was inserted along with the ID the student was given
+    Ok(format_student(&student))
+}
+
+fn insert_taken_course(store: &mut dyn RegistrarStore, taken_course: TakenCourse) -> Result<String> {
+    let taken_course = add_taken_course(store, taken_course)?;
+    // Send the OK! that the item was inserted
+    Ok(format_taken_course(&taken_course))
+}
+
+fn format_course(course: &Course) -> String {
+    format!("{} {} {} {}", course.prefix, course.number, course.title, course.credits)
+}
+
+fn format_student(student: &Student) -> String {
+    format!("#{} {} {} {}", student.id, student.lname, student.fname, student.phone)
+}
+
+fn format_taken_course(taken_course: &TakenCourse) -> String {
+    format!(
+        "#{} {} {} {} {}",
+        taken_course.student_id,
+        taken_course.course_prefix,
+        taken_course.course_number,
+        taken_course.grade_letter,
+        taken_course.semester_code,
+    )
+}
+
+/// Parses the number of credits a course is worth.
+fn parse_credits(item: &str, action: &str) -> Result<u8> {
+    match item.parse::<u8>() {
+        Ok(credits) => Ok(credits),
+        Err(_) => Err(RegistrarError::Validation(format!(
+            "{action}. Course credits must be a whole number from 0 to 255.",
+        ))),
+    }
+}
+
+/// Parses the point value of a grade.
+fn parse_grade_value(item: &str, action: &str) -> Result<Decimal> {
+    match item.parse::<Decimal>() {
+        // The Value column holds up to three digits, two after the decimal point
+        Ok(value) if value >= Decimal::ZERO && value < Decimal::TEN && value.scale() <= 2 => Ok(value),
+        _ => Err(RegistrarError::Validation(format!(
+            "{action}. Grade point value must be a number from 0 to 9.99.",
+        ))),
+    }
+}
+
+/// Flags that can follow a grade's value to change how it is handled on transcripts.
+const GRADE_FLAGS: [&str; 4] = ["--no-gpa", "--credit", "--no-credit", "--not-attempted"];
+
+/// Whether a new grade earns credit when neither --credit nor --no-credit is given. Only grades worth
+/// more than zero points do.
+fn earns_credit_by_default(value: Decimal) -> bool {
+    value > Decimal::ZERO
+}
+
+/// Parses a yes or no value given for one of a grade's flags.
+fn parse_yes_no(field: &str, item: &str) -> Result<bool> {
+    match item {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(RegistrarError::Validation(format!(
+            "Unable to edit item. Grade {field} must be yes or no.",
+        ))),
+    }
+}
+
+/// Formats a grade the way it would be entered, including the flags for whatever differs from the
+/// defaults.
+fn format_grade(grade: &Grade) -> String {
+    let mut item = format!("{} {}", grade.letter, grade.value);
+    if !grade.counts_toward_gpa {
+        item.push_str(" --no-gpa");
+    }
+    match (grade.earns_credit, earns_credit_by_default(grade.value)) {
+        (true, false) => item.push_str(" --credit"),
+        (false, true) => item.push_str(" --no-credit"),
+        _ => (),
+    }
+    if !grade.attempted {
+        item.push_str(" --not-attempted");
+    }
+    item
+}
+
+/// Parses the year of a semester.
+fn parse_year(item: &str, action: &str) -> Result<u16> {
+    match item.parse::<u16>() {
+        Ok(year) if (1..=9999).contains(&year) => Ok(year),
+        _ => Err(RegistrarError::Validation(format!(
+            "{action}. Semester year must be a whole number from 1 to 9999.",
+        ))),
+    }
+}
+
+/// Parses a semester's start or end date given as YYYY-MM-DD.
+fn parse_date(item: &str, action: &str) -> Result<Date> {
+    match date_from_str(item) {
+        Some(date) => Ok(date),
+        None => Err(RegistrarError::Validation(format!(
+            "{action}. Semester dates must be real dates given as YYYY-MM-DD.",
+        ))),
+    }
+}
+
+/// Parses the sequence number that orders a term within a year.
+fn parse_sequence(item: &str, action: &str) -> Result<u8> {
+    match item.parse::<u8>() {
+        Ok(sequence) => Ok(sequence),
+        Err(_) => Err(RegistrarError::Validation(format!(
+            "{action}. Term sequence must be a whole number from 0 to 255.",
+        ))),
+    }
+}
+
+/// Formats a semester the way it was entered, including whichever dates it has.
+fn format_semester(semester: &Semester) -> String {
+    let mut item = format!("{} {} {}", semester.code, semester.year, semester.description);
+    for date in [semester.start_date, semester.end_date].into_iter().flatten() {
+        item.push_str(&format!(" {date}"));
+    }
+    item
+}
+// End insert functions
+
+/// Flags that take a value, which can be given as --flag=value or as --flag value.
+const VALUE_FLAGS: [&str; 4] = ["--format", "--round", "--truncate", "--repeat"];
+
+/// Pulls the flags out so the rest of the input lines up the same way with or without them. A value
+/// given after one of the VALUE_FLAGS is joined back onto it as --flag=value.
+fn split_flags(input: Vec<&str>) -> (Vec<String>, Vec<&str>) {
+    let mut flags = Vec::new();
+    let mut rest = Vec::new();
+    let mut items = input.into_iter();
+    while let Some(item) = items.next() {
+        if !item.starts_with("--") {
+            rest.push(item);
+        } else if VALUE_FLAGS.contains(&item) {
+            // A flag missing its value is left as is to be reported as invalid
+            match items.next() {
+                Some(value) => flags.push(format!("{item}={value}")),
+                None => flags.push(item.to_string()),
+            }
+        } else {
+            flags.push(item.to_string());
+        }
+    }
+    (flags, rest)
+}
+
+// Start delete functions
+/// Function to delete items from the database. Possible items to delete are courses, grades, semesters, students, and taken courses.
+/// Deleting an item that taken courses use is refused unless --cascade is given, and --dry-run shows what would be deleted.
+pub fn delete_from_database(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<String> {
+    let (flags, input) = split_flags(input);
+    let mut policy = DeletePolicy::Refuse;
+    let mut dry_run = false;
+    for flag in flags {
+        match flag.as_str() {
+            "--cascade" => policy = DeletePolicy::Cascade,
+            "--dry-run" => dry_run = true,
+            _ => return Err(RegistrarError::Validation(format!(
+                "Invalid flag '{flag}'. Valid flags are --cascade and --dry-run.",
+            ))),
+        }
+    }
+    match input.get(1) {
+        Some(&"c") => {
+            let (prefix, number) = match (input.get(2), input.get(3)) {
+                (Some(prefix), Some(number)) => (prefix, number),
+                _ => return Err(RegistrarError::Validation(String::from(
+                    "Unable to delete item. Course needs a prefix and number.",
+                )))
+            };
+            match store.search_course(prefix, number)? {
+                Some(course) => delete_item(store, Referenced::Course(&course), format!("{} {}", course.prefix, course.number), policy, dry_run),
+                None => Err(does_not_exist("Unable to delete item", "course")),
+            }
+        }
+        Some(&"g") => {
+            let letter = match input.get(2) {
+                Some(letter) => letter,
+                None => return Err(RegistrarError::Validation(String::from(
+                    "Unable to delete item. Grade needs a type.",
+                )))
+            };
+            match store.search_grade(letter)? {
+                Some(grade) => delete_item(store, Referenced::Grade(&grade), grade.letter.clone(), policy, dry_run),
+                None => Err(does_not_exist("Unable to delete item", "grade")),
+            }
+        }
+        Some(&"m") => {
+            let code = match input.get(2) {
+                Some(code) => code,
+                None => return Err(RegistrarError::Validation(String::from(
+                    "Unable to delete item. Semester needs a code.",
+                )))
+            };
+            match store.search_semester(code)? {
+                Some(semester) => delete_item(store, Referenced::Semester(&semester), semester.code.clone(), policy, dry_run),
+                None => Err(does_not_exist("Unable to delete item", "semester")),
+            }
+        }
+        Some(&"r") => {
+            let name = match input.get(2) {
+                Some(name) => name,
+                None => return Err(RegistrarError::Validation(String::from(
+                    "Unable to delete item. Term needs a name.",
+                )))
+            };
+            let term = match store.search_term(name)? {
+                Some(term) => term,
+                None => return Err(does_not_exist("Unable to delete item", "term")),
+            };
+            // Semesters only name their term, so deleting it just sends them to the end of their year
+            if dry_run {
+                return Ok(format!("Dry run: '{}' would be deleted.", term.name));
+            }
+            store.delete_term(&term)?;
+            Ok(format!("Successfully deleted '{}' from database.", term.name))
+        }
+        Some(&"s") => {
+            let (student, _) = find_student(store, &input, 2, "Unable to delete item")?;
+            let name = format!("#{} {} {}", student.id, student.lname, student.fname);
+            delete_item(store, Referenced::Student(&student), name, policy, dry_run)
+        }
+        Some(&"t") => {
+            let (taken_course, _) = find_taken_course(store, &input, "Unable to delete item")?;
+            let name = format_taken_course(&taken_course);
+            // Nothing references a taken course so there is nothing to cascade
+            if dry_run {
+                return Ok(format!("Dry run: '{name}' would be deleted."));
+            }
+            store.delete_taken_course(&taken_course)?;
+            Ok(format!("Successfully deleted '{name}' from database."))
+        }
+        _ => Err(RegistrarError::Validation(String::from(
+            "Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ester, te(r)m, (s)tudent, and (t)aken course.",
+        ))),
+    }
+}
+
+fn delete_item(store: &mut dyn RegistrarStore, item: Referenced, name: String, policy: DeletePolicy, dry_run: bool) -> Result<String> {
+    let dependents = store.dependent_taken_courses(item)?;
+    if dry_run {
+        // Show the taken courses that would be affected without changing anything
+        if !dependents.is_empty() {
+            print_taken_courses(&dependents)?;
+        }
+        return Ok(match (dependents.len(), policy) {
+            (0, _) => format!("Dry run: '{name}' would be deleted. No taken courses use it."),
+            (count, DeletePolicy::Cascade) => format!("Dry run: '{name}' would be deleted along with the {count} taken course(s) above."),
+            (count, DeletePolicy::Refuse) => format!("Dry run: '{name}' would not be deleted since the {count} taken course(s) above use it. Use --cascade to delete them too."),
+        });
+    }
+    if policy == DeletePolicy::Refuse && !dependents.is_empty() {
+        return Err(RegistrarError::Constraint(format!(
+                "Unable to delete item. '{name}' is used by {} taken course(s). Use --dry-run to see them or --cascade to delete them too.",
+                dependents.len()
+            )));
+    }
+    let deleted = store.delete_item(item, policy)?;
+    if deleted > 0 {
+        Ok(format!("Successfully deleted '{name}' and {deleted} taken course(s) from database."))
+    } else {
+        Ok(format!("Successfully deleted '{name}' from database."))
+    }
+}
+
+// End delete functions
+
+// Start edit functions
+/// Function to edit items in the database. Possible items to edit are courses, grades, semesters, students, and taken courses.
+/// The item is picked out by its key and the changes are given as field=value.
+pub fn edit_in_database(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<String> {
+    let item = match input.get(1) {
+        Some(&"c") => edit_course(store, &input)?,
+        Some(&"g") => edit_grade(store, &input)?,
+        Some(&"m") => edit_semester(store, &input)?,
+        Some(&"r") => edit_term(store, &input)?,
+        Some(&"s") => edit_student(store, &input)?,
+        Some(&"t") => edit_taken_course(store, &input)?,
+        _ => return Err(RegistrarError::Validation(String::from(
+            "Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ester, te(r)m, (s)tudent, and (t)aken course.",
+        ))),
+    };
+    Ok(item)
+}
+
+fn edit_course(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let (prefix, number) = match (input.get(2), input.get(3)) {
+        (Some(prefix), Some(number)) => (prefix, number),
+        _ => return Err(RegistrarError::Validation(String::from(
+            "Unable to edit item. Course needs a prefix and number.",
+        )))
+    };
+    let old = match store.search_course(prefix, number)? {
+        Some(course) => course,
+        None => return Err(does_not_exist("Unable to edit item", "course")),
+    };
+    let mut course = old.clone();
+    for (field, value) in parse_changes(input, 4)? {
+        match field {
+            "prefix" => course.prefix = value.to_string(),
+            "number" => course.number = value.to_string(),
+            "title" => course.title = value.to_string(),
+            "credits" => course.credits = parse_credits(value, "Unable to edit item")?,
+            _ => return Err(invalid_field(field, "prefix, number, title, and credits")),
+        }
+    }
+    let course = update_course(store, &old, course)?;
+    // Send the OK! that the item was updated
+    Ok(format_course(&course))
+}
+
+fn edit_grade(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let letter = match input.get(2) {
+        Some(letter) => letter,
+        None => return Err(RegistrarError::Validation(String::from(
+            "Unable to edit item. Grade needs a type.",
+        )))
+    };
+    let old = match store.search_grade(letter)? {
+        Some(grade) => grade,
+        None => return Err(does_not_exist("Unable to edit item", "grade")),
+    };
+    let mut grade = old.clone();
+    for (field, value) in parse_changes(input, 3)? {
+        match field {
+            "type" => grade.letter = value.to_string(),
+            "value" => grade.value = parse_grade_value(value, "Unable to edit item")?,
+            "gpa" => grade.counts_toward_gpa = parse_yes_no(field, value)?,
+            "credit" => grade.earns_credit = parse_yes_no(field, value)?,
+            "attempted" => grade.attempted = parse_yes_no(field, value)?,
+            _ => return Err(invalid_field(field, "type, value, gpa, credit, and attempted")),
+        }
+    }
+    let grade = update_grade(store, &old, grade)?;
+    // Send the OK! that the item was updated
+    Ok(format_grade(&grade))
+}
+
+fn edit_semester(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let code = match input.get(2) {
+        Some(code) => code,
+        None => return Err(RegistrarError::Validation(String::from(
+            "Unable to edit item. Semester needs a code.",
+        )))
+    };
+    let old = match store.search_semester(code)? {
+        Some(semester) => semester,
+        None => return Err(does_not_exist("Unable to edit item", "semester")),
+    };
+    let mut semester = old.clone();
+    for (field, value) in parse_changes(input, 3)? {
+        match field {
+            "code" => semester.code = value.to_string(),
+            "year" => semester.year = parse_year(value, "Unable to edit item")?,
+            "desc" => semester.description = value.to_string(),
+            // Leaving the value empty clears the date
+            "start" if value.is_empty() => semester.start_date = None,
+            "start" => semester.start_date = Some(parse_date(value, "Unable to edit item")?),
+            "end" if value.is_empty() => semester.end_date = None,
+            "end" => semester.end_date = Some(parse_date(value, "Unable to edit item")?),
+            _ => return Err(invalid_field(field, "code, year, desc, start, and end")),
+        }
+    }
+    let semester = update_semester(store, &old, semester)?;
+    // Send the OK! that the item was updated
+    Ok(format_semester(&semester))
+}
+
+fn edit_term(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let name = match input.get(2) {
+        Some(name) => name,
+        None => return Err(RegistrarError::Validation(String::from(
+            "Unable to edit item. Term needs a name.",
+        )))
+    };
+    let old = match store.search_term(name)? {
+        Some(term) => term,
+        None => return Err(does_not_exist("Unable to edit item", "term")),
+    };
+    let mut term = old.clone();
+    for (field, value) in parse_changes(input, 3)? {
+        match field {
+            "name" => term.name = value.to_string(),
+            "sequence" => term.sequence = parse_sequence(value, "Unable to edit item")?,
+            _ => return Err(invalid_field(field, "name and sequence")),
+        }
+    }
+    let term = update_term(store, &old, term)?;
+    // Send the OK! that the item was updated
+    Ok(format!("{} {}", term.name, term.sequence))
+}
+
+fn edit_student(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let (old, next) = find_student(store, input, 2, "Unable to edit item")?;
+    let mut student = old.clone();
+    for (field, value) in parse_changes(input, next)? {
+        match field {
+            "id" => student.id = parse_student_id(value, "Unable to edit item")?,
+            "lname" => student.lname = value.to_string(),
+            "fname" => student.fname = value.to_string(),
+            "phone" => student.phone = value.to_string(),
+            _ => return Err(invalid_field(field, "id, lname, fname, and phone")),
+        }
+    }
+    let student = update_student(store, &old, student)?;
+    // Send the OK! that the item was updated
+    Ok(format_student(&student))
+}
+
+fn edit_taken_course(store: &mut dyn RegistrarStore, input: &[&str]) -> Result<String> {
+    let (old, next) = find_taken_course(store, input, "Unable to edit item")?;
+    let mut taken_course = old.clone();
+    for (field, value) in parse_changes(input, next)? {
+        match field {
+            "student" => taken_course.student_id = parse_student_id(value.trim_start_matches('#'), "Unable to edit item")?,
+            "prefix" => taken_course.course_prefix = value.to_string(),
+            "number" => taken_course.course_number = value.to_string(),
+            "type" => taken_course.grade_letter = value.to_string(),
+            "code" => taken_course.semester_code = value.to_string(),
+            _ => return Err(invalid_field(field, "student, prefix, number, type, and code")),
+        }
+    }
+    let taken_course = update_taken_course(store, &old, taken_course)?;
+    // Send the OK! that the item was updated
+    Ok(format_taken_course(&taken_course))
+}
+
+/// Splits the field=value changes at the end of an edit command starting at the given index.
+fn parse_changes<'a>(input: &[&'a str], index: usize) -> Result<Vec<(&'a str, &'a str)>> {
+    let mut changes = Vec::new();
+    for item in input.iter().skip(index) {
+        match item.split_once('=') {
+            Some(change) => changes.push(change),
+            None => return Err(RegistrarError::Validation(format!(
+                "Unable to edit item. Change '{item}' needs to be given as field=value.",
+            ))),
+        }
+    }
+    if changes.is_empty() {
+        return Err(RegistrarError::Validation(String::from(
+            "Unable to edit item. No changes supplied. Give changes as field=value.",
+        )));
+    }
+    Ok(changes)
+}
+
+fn invalid_field(field: &str, valid: &str) -> RegistrarError {
+    RegistrarError::Validation(format!(
+        "Unable to edit item. Invalid field '{field}'. Valid fields are {valid}.",
+    ))
+}
+
+fn does_not_exist(action: &str, item: &str) -> RegistrarError {
+    RegistrarError::NotFound(format!("{action}. Given {item} does not exist."))
+}
+// End edit functions
+
+// Start list functions
+/// Function to list items from the database. Possible items to list are courses, grades, semesters, students, and taken courses.
+/// They are printed as a table unless --format=csv, tsv, json, or jsonl is given.
+pub fn list_from_database(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<()> {
+    let (flags, input) = split_flags(input);
+    let mut format = Format::Table;
+    for flag in flags {
+        format = match flag.split_once('=') {
+            Some(("--format", name)) => match Format::from_name(name) {
+                Some(format) => format,
+                None => return Err(RegistrarError::Validation(String::from(
+                    "Unable to list items. Format must be table, csv, tsv, json, or jsonl.",
+                ))),
+            },
+            _ => return Err(RegistrarError::Validation(format!(
+                "Invalid flag '{flag}'. Valid flags are --format=FORMAT.",
+            ))),
+        };
+    }
+    // Select all rows from table
+    let output = match input.get(1) {
+        Some(&"c") => render(&store.list_courses()?, format)?,
+        Some(&"g") => render(&store.list_grades()?, format)?,
+        Some(&"m") => render(&store.list_semesters()?, format)?,
+        Some(&"r") => render(&store.list_terms()?, format)?,
+        Some(&"s") => render(&store.list_students()?, format)?,
+        Some(&"t") => render(&store.list_taken_courses()?, format)?,
+        _ => return Err(RegistrarError::Validation(String::from(
+            "Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ster, te(r)m, (s)tudent, (t)aken course.",
+        ))),
+    };
+    print!("{output}");
+    // Leave a blank line after tables but keep the other formats clean for other programs to read
+    if format == Format::Table {
+        println!();
+    }
+    Ok(())
+}
+
+fn print_taken_courses(taken_courses: &[TakenCourse]) -> Result<()> {
+    println!("{}", render(taken_courses, Format::Table)?);
+    Ok(())
+}
+// End list functions
+
+/// Function to print a student's transcript. The GPA is rounded to two places unless --round=N or
+/// --truncate=N is given, and only the latest attempt of a repeated course counts unless
+/// --repeat=average or --repeat=highest is given. --format=FORMAT prints it as a table, CSV, TSV,
+/// JSON, JSON Lines, Markdown, or HTML instead of plain text.
+pub fn transcript(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<()> {
+    let (flags, input) = split_flags(input);
+    let mut rule = GpaRule::default();
+    let mut policy = RepeatPolicy::Replace;
+    let mut renderer: Box<dyn TranscriptRenderer> = Box::new(TextRenderer);
+    let mut text = true;
+    for flag in flags {
+        let (places, truncate) = match flag.split_once('=') {
+            Some(("--round", places)) => (places, false),
+            Some(("--truncate", places)) => (places, true),
+            Some(("--repeat", name)) => {
+                policy = match name {
+                    "replace" => RepeatPolicy::Replace,
+                    "average" => RepeatPolicy::Average,
+                    "highest" => RepeatPolicy::Highest,
+                    _ => return Err(RegistrarError::Validation(String::from(
+                        "Unable to print transcript. Repeat policy must be replace, average, or highest.",
+                    ))),
+                };
+                continue;
+            }
+            Some(("--format", name)) => {
+                renderer = match transcript::renderer(name) {
+                    Some(renderer) => renderer,
+                    None => return Err(RegistrarError::Validation(String::from(
+                        "Unable to print transcript. Format must be text, table, csv, tsv, json, jsonl, markdown, or html.",
+                    ))),
+                };
+                text = matches!(name, "text" | "table");
+                continue;
+            }
+            _ => return Err(RegistrarError::Validation(format!(
+                "Invalid flag '{flag}'. Valid flags are --round=N, --truncate=N, --repeat=POLICY, and --format=FORMAT.",
+            ))),
+        };
+        rule = GpaRule {
+            // Decimal numbers hold at most 28 digits after the decimal point
+            places: match places.parse::<u32>() {
+                Ok(places) if places <= 28 => places,
+                _ => return Err(RegistrarError::Validation(String::from(
+                    "Unable to print transcript. Decimal places must be a whole number from 0 to 28.",
+                ))),
+            },
+            truncate,
+        };
+    }
+    let (student, _) = find_student(store, &input, 1, "Unable to print transcript")?;
+    let transcript = Transcript::load(store, student, policy, rule)?;
+    if transcript.terms.is_empty() {
+        return Err(RegistrarError::NotFound(String::from(
+            "Unable to print transcript. Given student either does not exist or has not taken any courses yet.",
+        )));
+    }
+    print!("{}", renderer.render(&transcript)?);
+    // Leave a blank line after text but keep the other formats clean for other programs to read
+    if text {
+        println!();
+    }
+    Ok(())
+}
+
+// Start lookup functions
+/// Finds the student named by the input starting at the given index. A student can be given by ID
+/// as #<id> or by last and first name. Returns the student and the index of the next item after it.
+fn find_student(store: &mut dyn RegistrarStore, input: &[&str], index: usize, action: &str) -> Result<(Student, usize)> {
+    // Check if user actually entered something
+    let lname = match input.get(index) {
+        Some(item) => item,
+        None => return Err(RegistrarError::Validation(format!("{action}. No student supplied.")))
+    };
+    let (key, next) = match lname.strip_prefix('#') {
+        Some(id) => (StudentKey::Id(parse_student_id(id, action)?), index + 1),
+        None => {
+            // Check if user actually entered something
+            let fname = match input.get(index + 1) {
+                Some(item) => item,
+                None => return Err(RegistrarError::Validation(format!(
+                    "{action}. Student's first name not supplied.",
+                )))
+            };
+            (StudentKey::Name { lname: lname.to_string(), fname: fname.to_string() }, index + 2)
+        }
+    };
+    let student = registrar::dbmgmt::find_student(store, &key).map_err(|error| error.during(action))?;
+    Ok((student, next))
+}
+
+/// Parses the number after the # of a student ID.
+fn parse_student_id(item: &str, action: &str) -> Result<u32> {
+    match item.parse::<u32>() {
+        Ok(id) if id > 0 => Ok(id),
+        _ => Err(RegistrarError::Validation(format!(
+            "{action}. Student ID must be a positive whole number.",
+        ))),
+    }
+}
+/// Finds the taken course named by the input after the subcommand. It is given by the student, the
+/// course's prefix and number, and the semester code, followed by the grade type if the student was
+/// graded more than once for the course that semester. Returns the taken course and the index of the
+/// next item after it.
+fn find_taken_course(store: &mut dyn RegistrarStore, input: &[&str], action: &str) -> Result<(TakenCourse, usize)> {
+    let (student, next) = find_student(store, input, 2, action)?;
+    let (prefix, number, code) = match (input.get(next), input.get(next + 1), input.get(next + 2)) {
+        (Some(prefix), Some(number), Some(code)) => (prefix, number, code),
+        _ => return Err(RegistrarError::Validation(format!(
+            "{action}. Taken course needs a course's prefix and number and a semester code.",
+        )))
+    };
+    // The grade only has to be given when the course was graded more than once that semester
+    let (letter, next) = match input.get(next + 3) {
+        Some(item) if !item.contains('=') => (Some(*item), next + 4),
+        _ => (None, next + 3),
+    };
+    let taken_course = registrar::dbmgmt::find_taken_course(store, &student, prefix, number, code, letter)
+        .map_err(|error| error.during(action))?;
+    Ok((taken_course, next))
+}
+// End lookup functions
+
+// Start migrate functions
+/// Function to run the migrate command. Possible subcommands are status, up, and down, where up and
+/// down can be given the version to migrate to.
+pub fn migrate(store: &mut dyn RegistrarStore, input: Vec<&str>) -> Result<()> {
+    // Check if the user gave a version to migrate to
+    let target = match input.get(2) {
+        Some(item) => match item.parse::<u32>() {
+            Ok(version) => Some(version),
+            Err(_) => return Err(RegistrarError::Validation(String::from(
+                "Unable to migrate database. Version must be a number.",
+            ))),
+        },
+        None => None,
+    };
+    match input.get(1) {
+        Some(&"status") => print_status(store)?,
+        Some(&"up") => {
+            let applied = migrate_up(store, target)?;
+            if applied.is_empty() {
+                println!("Database is already up to date.");
+            }
+            for migration in applied {
+                println!("Applied migration {}: {}", migration.version, migration.description);
+            }
+            println!();
+        }
+        Some(&"down") => {
+            let reverted = migrate_down(store, target)?;
+            if reverted.is_empty() {
+                println!("No migrations to revert.");
+            }
+            for migration in reverted {
+                println!("Reverted migration {}: {}", migration.version, migration.description);
+            }
+            println!();
+        }
+        _ => return Err(RegistrarError::Validation(String::from(
+            "Invalid subcommand. Valid subcommands are status, up, and down.",
+        ))),
+    }
+    Ok(())
+}
+
+fn print_status(store: &mut dyn RegistrarStore) -> Result<()> {
+    let migrations = migrations::status(store)?;
+    // Print out header for table
+    println!("+---------+------------------------------------------+---------+");
+    println!("| Version | Description                              | Status  |");
+    println!("+---------+------------------------------------------+---------+");
+    // Iterate through migrations and print out styled table
+    for (migration, applied) in migrations {
+        let status = if applied { "applied" } else { "pending" };
+        println!("| {:>7} | {:<40} | {:<7} |", migration.version, migration.description, status);
+    }
+    println!("+---------+------------------------------------------+---------+\n");
+    Ok(())
+}
+// End migrate functions
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use registrar::memory_store::MemoryStore;
+
+    fn run(store: &mut MemoryStore, line: &str) -> Result<String> {
+        insert_into_database(store, line.split_whitespace().collect())
+    }
+
+    /// A store with a student who took one course that semester.
+    fn filled_store() -> MemoryStore {
+        let mut store = MemoryStore::new();
+        for line in [
+            "a c CSC 300 Databases 3",
+            "a g A 4.0",
+            "a g B 3.0",
+            "a m F23 2023 Fall",
+            "a s #1 Smith John 555-1234",
+            "a t #1 CSC 300 A F23",
+        ] {
+            run(&mut store, line).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn add_commands_insert_items() {
+        let mut store = filled_store();
+        assert_eq!(run(&mut store, "a s Doe Jane 555-0000").unwrap(), "#2 Doe Jane 555-0000");
+        assert_eq!(store.list_courses().unwrap().len(), 1);
+        assert_eq!(store.list_taken_courses().unwrap()[0].grade_letter, "A");
+        assert!(list_from_database(&mut store, vec!["l", "t", "--format", "json"]).is_ok());
+    }
+
+    #[test]
+    fn adding_a_duplicate_is_reported() {
+        let mut store = filled_store();
+        for line in ["a c CSC 300 Other 4", "a g A 3.5", "a s #1 Doe Jane 555-0000", "a t #1 CSC 300 A F23"] {
+            assert!(matches!(run(&mut store, line), Err(RegistrarError::Duplicate(_))), "{line}");
+        }
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        let mut store = filled_store();
+        for line in [
+            "a x",
+            "a c CSC",
+            "a c CSC 301 Networks many",
+            "a g C 12",
+            "a m S24 twenty Spring",
+            "a s #0 Doe Jane 555-0000",
+            "a t Nobody Here CSC 300 A F23",
+        ] {
+            assert!(matches!(run(&mut store, line), Err(RegistrarError::Validation(_) | RegistrarError::NotFound(_))), "{line}");
+        }
+        assert!(matches!(list_from_database(&mut store, vec!["l", "x"]), Err(RegistrarError::Validation(_))));
+        assert!(matches!(
+            list_from_database(&mut store, vec!["l", "c", "--format=xml"]),
+            Err(RegistrarError::Validation(_))
+        ));
+        assert!(matches!(
+            delete_from_database(&mut store, vec!["d", "c", "CSC"]),
+            Err(RegistrarError::Validation(_))
+        ));
+        assert!(matches!(
+            transcript(&mut store, vec!["t", "#1", "--round=99"]),
+            Err(RegistrarError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn taken_courses_need_existing_items() {
+        let mut store = filled_store();
+        let result = run(&mut store, "a t #1 CSC 999 A F23");
+        assert!(matches!(result, Err(RegistrarError::Constraint(_))));
+    }
+
+    #[test]
+    fn delete_refuses_referenced_items_unless_cascaded() {
+        let mut store = filled_store();
+        let result = delete_from_database(&mut store, vec!["d", "c", "CSC", "300"]);
+        assert!(matches!(result, Err(RegistrarError::Constraint(_))));
+        delete_from_database(&mut store, vec!["d", "c", "CSC", "300", "--dry-run", "--cascade"]).unwrap();
+        assert_eq!(store.list_courses().unwrap().len(), 1);
+        delete_from_database(&mut store, vec!["d", "c", "CSC", "300", "--cascade"]).unwrap();
+        assert!(store.list_courses().unwrap().is_empty());
+        assert!(store.list_taken_courses().unwrap().is_empty());
+        let result = delete_from_database(&mut store, vec!["d", "g", "Z"]);
+        assert!(matches!(result, Err(RegistrarError::NotFound(_))));
+    }
+
+    #[test]
+    fn transcript_totals_add_up() {
+        let mut store = filled_store();
+        run(&mut store, "a c MTH 101 Algebra 4").unwrap();
+        run(&mut store, "a t #1 MTH 101 B F23").unwrap();
+        transcript(&mut store, vec!["t", "Smith", "John", "--format", "jsonl"]).unwrap();
+        let student = store.search_student(1).unwrap().unwrap();
+        let loaded = Transcript::load(&mut store, student, RepeatPolicy::Replace, GpaRule::default()).unwrap();
+        assert_eq!(loaded.totals.hours_completed, 7);
+        // (3 * 4.0 + 4 * 3.0) / 7 = 3.428...
+        assert_eq!(loaded.totals.gpa, Some(Decimal::new(343, 2)));
+    }
+
+    #[test]
+    fn failing_grades_earn_no_credit() {
+        let mut store = filled_store();
+        assert_eq!(run(&mut store, "a g F 0").unwrap(), "F 0");
+        assert_eq!(run(&mut store, "a g P 0 --no-gpa --credit").unwrap(), "P 0 --no-gpa --credit");
+        assert!(matches!(run(&mut store, "a g NC 0 --credit --no-credit"), Err(RegistrarError::Validation(_))));
+        run(&mut store, "a c MTH 101 Algebra 4").unwrap();
+        run(&mut store, "a t #1 MTH 101 F F23").unwrap();
+        let student = store.search_student(1).unwrap().unwrap();
+        let loaded = Transcript::load(&mut store, student, RepeatPolicy::Replace, GpaRule::default()).unwrap();
+        assert_eq!(loaded.totals.hours_attempted, 7);
+        assert_eq!(loaded.totals.hours_completed, 3);
+        assert_eq!(loaded.totals.gpa_hours, 7);
+    }
+
+    #[test]
+    fn transcript_without_courses_is_not_found() {
+        let mut store = filled_store();
+        run(&mut store, "a s #2 Doe Jane 555-0000").unwrap();
+        let result = transcript(&mut store, vec!["t", "#2"]);
+        assert!(matches!(result, Err(RegistrarError::NotFound(_))));
+    }
+
+    #[test]
+    fn flag_values_can_follow_with_a_space() {
+        let (flags, rest) = split_flags(vec!["t", "Smith", "--format", "json", "John", "--round=3", "--truncate", "2"]);
+        assert_eq!(flags, vec!["--format=json", "--round=3", "--truncate=2"]);
+        assert_eq!(rest, vec!["t", "Smith", "John"]);
+    }
+
+    #[test]
+    fn flags_without_values_are_left_alone() {
+        let (flags, rest) = split_flags(vec!["l", "c", "--dry-run", "s", "--format"]);
+        assert_eq!(flags, vec!["--dry-run", "--format"]);
+        assert_eq!(rest, vec!["l", "c", "s"]);
+    }
+}
@@ -0,0 +1,276 @@
+// Author: Grant Duchars
+use crate::error::{RegistrarError, Result};
+use mysql::{ClientIdentity, SslOpts};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The config file used when no other one is given.
+pub const DEFAULT_CONFIG_FILE: &str = "registrar.toml";
+
+/// The layout of the config file. Each profile is a table under [profiles], like [profiles.prod].
+#[derive(Deserialize, Default)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+}
+
+/// Connection settings for one profile. Anything left out is taken from the environment or
+/// prompted for.
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// A full connection url. Cannot be mixed with the hostname, port, username and database.
+    pub url: Option<String>,
+    pub hostname: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub database: Option<String>,
+    /// A file holding the password, like a mounted secret.
+    pub password_file: Option<PathBuf>,
+    /// An open file descriptor to read the password from.
+    pub password_fd: Option<u32>,
+    /// Refuse to connect to MySQL without TLS. Giving any of the other ssl settings also turns it on.
+    pub require_ssl: Option<bool>,
+    /// The CA bundle used to verify the server's certificate instead of the system's.
+    pub ssl_ca: Option<PathBuf>,
+    /// A PKCS #12 archive holding the client certificate and key to connect with.
+    pub ssl_client_cert: Option<PathBuf>,
+    /// The password protecting the client certificate archive.
+    pub ssl_client_cert_password: Option<String>,
+    /// Accept any server certificate. Only meant for local test servers with self-signed certificates.
+    pub ssl_skip_verify: Option<bool>,
+}
+
+impl Profile {
+    /// Fills in the settings set by environment variables, which take priority over the config file.
+    /// DATABASE_URL is left out when the profile was picked on purpose, since it would otherwise
+    /// quietly connect somewhere other than the profile says.
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>, chosen: bool) -> Result<()> {
+        if let Some(url) = var("DATABASE_URL").filter(|_| !chosen) {
+            self.url = Some(url);
+        }
+        if let Some(hostname) = var("REGISTRAR_HOSTNAME") {
+            self.hostname = Some(hostname);
+        }
+        if let Some(port) = var("REGISTRAR_PORT") {
+            self.port = Some(parse_number(&port, "REGISTRAR_PORT")?);
+        }
+        if let Some(username) = var("REGISTRAR_USERNAME") {
+            self.username = Some(username);
+        }
+        if let Some(database) = var("REGISTRAR_DATABASE") {
+            self.database = Some(database);
+        }
+        if let Some(path) = var("REGISTRAR_PASSWORD_FILE") {
+            self.password_file = Some(PathBuf::from(path));
+        }
+        if let Some(fd) = var("REGISTRAR_PASSWORD_FD") {
+            self.password_fd = Some(parse_number(&fd, "REGISTRAR_PASSWORD_FD")?);
+        }
+        if let Some(require) = var("REGISTRAR_REQUIRE_SSL") {
+            self.require_ssl = Some(parse_bool(&require, "REGISTRAR_REQUIRE_SSL")?);
+        }
+        if let Some(path) = var("REGISTRAR_SSL_CA") {
+            self.ssl_ca = Some(PathBuf::from(path));
+        }
+        if let Some(path) = var("REGISTRAR_SSL_CLIENT_CERT") {
+            self.ssl_client_cert = Some(PathBuf::from(path));
+        }
+        if let Some(password) = var("REGISTRAR_SSL_CLIENT_CERT_PASSWORD") {
+            self.ssl_client_cert_password = Some(password);
+        }
+        if let Some(skip) = var("REGISTRAR_SSL_SKIP_VERIFY") {
+            self.ssl_skip_verify = Some(parse_bool(&skip, "REGISTRAR_SSL_SKIP_VERIFY")?);
+        }
+        // A url would silently win over the separate settings, so giving both is a mistake
+        let separate =
+            self.hostname.is_some() || self.port.is_some() || self.username.is_some() || self.database.is_some();
+        if self.url.is_some() && separate {
+            return Err(RegistrarError::Validation(String::from(
+                "Unable to load config. Give either a url or the hostname, port, username and database, not both.",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Builds the TLS options for a MySQL connection, or None if TLS was not asked for.
+    pub fn ssl_opts(&self) -> Option<SslOpts> {
+        let skip_verify = self.ssl_skip_verify.unwrap_or(false);
+        let wanted = self.require_ssl.unwrap_or(false)
+            || self.ssl_ca.is_some()
+            || self.ssl_client_cert.is_some()
+            || skip_verify;
+        if !wanted {
+            return None;
+        }
+        // The client certificate is a PKCS #12 archive, which may be protected by a password
+        let identity = self.ssl_client_cert.clone().map(|path| {
+            let identity = ClientIdentity::new(path);
+            match self.ssl_client_cert_password.clone() {
+                Some(password) => identity.with_password(password),
+                None => identity,
+            }
+        });
+        let ssl_opts = SslOpts::default()
+            .with_root_cert_path(self.ssl_ca.clone())
+            .with_client_identity(identity)
+            .with_danger_accept_invalid_certs(skip_verify)
+            .with_danger_skip_domain_validation(skip_verify);
+        Some(ssl_opts)
+    }
+
+    /// Reads the password from the file descriptor or secret file if either was given. The file
+    /// descriptor wins if both are.
+    pub fn read_password(&self) -> Result<Option<String>> {
+        let path = match (self.password_fd, &self.password_file) {
+            // Open file descriptors can be read through /dev/fd without taking ownership of them
+            (Some(fd), _) => PathBuf::from(format!("/dev/fd/{fd}")),
+            (None, Some(path)) => path.clone(),
+            (None, None) => return Ok(None),
+        };
+        match fs::read_to_string(&path) {
+            // Secret files usually end with a newline that is not part of the password
+            Ok(password) => Ok(Some(password.trim_end_matches(['\r', '\n']).to_string())),
+            Err(error) => Err(RegistrarError::Internal(format!(
+                "Unable to read password from '{}'. {error}", path.display(),
+            ))),
+        }
+    }
+}
+
+/// Loads the connection settings for the named profile. The config file is the one given, or the
+/// one named by REGISTRAR_CONFIG, or registrar.toml in the working directory if it exists. The
+/// profile is the one given, or the one named by REGISTRAR_PROFILE, or "default".
+///
+/// The REGISTRAR_* environment variables override whatever the profile sets. DATABASE_URL does too,
+/// except when a profile was picked with --profile or REGISTRAR_PROFILE, in which case the profile
+/// wins. A url and the separate hostname, port, username or database settings cannot be mixed, so
+/// ending up with both is an error instead of one quietly being ignored.
+pub fn load(path: Option<&str>, profile: Option<&str>) -> Result<Profile> {
+    let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+    let path = path.map(String::from).or_else(|| var("REGISTRAR_CONFIG"));
+    let profile = profile.map(String::from).or_else(|| var("REGISTRAR_PROFILE"));
+    // A missing config file is only a problem if the user asked for one
+    let config = match &path {
+        Some(path) => read_config(Path::new(path))?,
+        None if Path::new(DEFAULT_CONFIG_FILE).exists() => read_config(Path::new(DEFAULT_CONFIG_FILE))?,
+        None => ConfigFile::default(),
+    };
+    select_profile(&config, profile.as_deref(), var)
+}
+
+/// Picks the profile out of the config file and applies the environment on top of it.
+fn select_profile(
+    config: &ConfigFile,
+    profile: Option<&str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<Profile> {
+    let mut settings = match (config.profiles.get(profile.unwrap_or("default")), profile) {
+        (Some(settings), _) => settings.clone(),
+        (None, None) => Profile::default(),
+        (None, Some(name)) => {
+            return Err(RegistrarError::NotFound(format!(
+                "Unable to load config. There is no profile named '{name}'.",
+            )))
+        }
+    };
+    settings.apply_env(var, profile.is_some())?;
+    Ok(settings)
+}
+
+fn read_config(path: &Path) -> Result<ConfigFile> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            return Err(RegistrarError::Internal(format!(
+                "Unable to read config file '{}'. {error}", path.display(),
+            )))
+        }
+    };
+    toml::from_str(&text).map_err(|error| {
+        RegistrarError::Validation(format!("Unable to read config file '{}'. {error}", path.display()))
+    })
+}
+
+fn parse_bool(value: &str, name: &str) -> Result<bool> {
+    match value {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(RegistrarError::Validation(format!("Unable to load config. {name} must be true or false."))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, name: &str) -> Result<T> {
+    match value.parse::<T>() {
+        Ok(number) => Ok(number),
+        Err(_) => Err(RegistrarError::Validation(format!(
+            "Unable to load config. {name} must be a whole number.",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[profiles.default]
+url = "sqlite://registrar.db"
+
+[profiles.prod]
+hostname = "db.example.com"
+port = 3306
+username = "registrar"
+database = "registrar"
+
+[profiles.broken]
+url = "sqlite://broken.db"
+hostname = "db.example.com"
+"#;
+
+    fn select(profile: Option<&str>, env: &[(&str, &str)]) -> Result<Profile> {
+        let config: ConfigFile = toml::from_str(CONFIG).unwrap();
+        let var = |name: &str| env.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string());
+        select_profile(&config, profile, var)
+    }
+
+    #[test]
+    fn database_url_overrides_the_default_profile() {
+        let settings = select(None, &[("DATABASE_URL", "memory://")]).unwrap();
+        assert_eq!(settings.url.as_deref(), Some("memory://"));
+    }
+
+    #[test]
+    fn chosen_profile_wins_over_database_url() {
+        let settings = select(Some("default"), &[("DATABASE_URL", "memory://")]).unwrap();
+        assert_eq!(settings.url.as_deref(), Some("sqlite://registrar.db"));
+        let settings = select(Some("prod"), &[("DATABASE_URL", "memory://")]).unwrap();
+        assert_eq!(settings.url, None);
+        assert_eq!(settings.hostname.as_deref(), Some("db.example.com"));
+    }
+
+    #[test]
+    fn environment_overrides_profile_settings() {
+        let settings = select(Some("prod"), &[("REGISTRAR_HOSTNAME", "localhost"), ("REGISTRAR_PORT", "3307")]).unwrap();
+        assert_eq!(settings.hostname.as_deref(), Some("localhost"));
+        assert_eq!(settings.port, Some(3307));
+        assert_eq!(settings.username.as_deref(), Some("registrar"));
+    }
+
+    #[test]
+    fn url_and_separate_settings_are_refused() {
+        assert!(matches!(select(Some("broken"), &[]), Err(RegistrarError::Validation(_))));
+        assert!(matches!(select(None, &[("REGISTRAR_HOSTNAME", "localhost")]), Err(RegistrarError::Validation(_))));
+        assert!(select(Some("prod"), &[("DATABASE_URL", "memory://")]).is_ok());
+        assert!(select(None, &[("DATABASE_URL", "memory://"), ("REGISTRAR_PORT", "3306")]).is_err());
+    }
+
+    #[test]
+    fn missing_profile_is_not_found() {
+        assert!(matches!(select(Some("nope"), &[]), Err(RegistrarError::NotFound(_))));
+        assert!(select(None, &[]).is_ok());
+    }
+}
@@ -1,772 +1,286 @@
 // Author: Grant Duchars
-use mysql::prelude::*;
-use mysql::*;
-use std::{io::{Error, ErrorKind}, cmp::Ordering};
+use crate::error::{RegistrarError, Result};
+use crate::store::{Referenced, RegistrarStore};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use time::{Date, Month};
 
-struct Course {
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Course {
     pub prefix: String,
     pub number: String,
     pub title: String,
-    pub credits: String,
+    pub credits: u8,
 }
 
-struct Grade {
+/// A grade type along with how it is handled on transcripts. Grades like W, I, P, AU and NR are
+/// recorded with taken courses but leave the GPA, the credit earned, or both alone.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Grade {
     pub letter: String,
-    pub value: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub value: Decimal,
+    /// The course's credits and quality points are part of the GPA.
+    pub counts_toward_gpa: bool,
+    /// The course's credits count as hours completed.
+    pub earns_credit: bool,
+    /// The course's credits count as hours attempted.
+    pub attempted: bool,
 }
 
-#[derive(PartialEq, Eq, PartialOrd)]
-struct Semester {
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Semester {
     pub code: String,
-    pub year: String,
+    pub year: u16,
     pub description: String,
+    pub start_date: Option<Date>,
+    pub end_date: Option<Date>,
 }
 
-impl Ord for Semester {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let year_int = self.year.parse::<i32>().unwrap();
-        let other_year_int = other.year.parse::<i32>().unwrap();
-        if year_int < other_year_int {
-            Ordering::Less
-        } else if year_int > other_year_int {
-            Ordering::Greater
-        } else {
-            Ordering::Equal
-        }
+impl Semester {
+    /// Key that puts semesters in academic calendar order. Semesters are ordered by year, then by
+    /// the sequence of the term named by their description, then by start date, and finally by code.
+    /// Semesters without a known term or a start date come after the ones with them.
+    pub fn calendar_key(&self, terms: &[Term]) -> (u16, u8, bool, Option<Date>, String) {
+        let sequence = terms
+            .iter()
+            .find(|term| term.name == self.description)
+            .map_or(u8::MAX, |term| term.sequence);
+        (self.year, sequence, self.start_date.is_none(), self.start_date, self.code.clone())
     }
 }
 
-struct Student {
+/// A term of the academic year like Spring or Fall. The sequence gives its order within a year.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Term {
+    pub name: String,
+    pub sequence: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Student {
+    pub id: u32,
     pub lname: String,
     pub fname: String,
     pub phone: String,
 }
 
-struct TakenCourse {
-    pub student_lname: String,
-    pub student_fname: String,
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TakenCourse {
+    pub student_id: u32,
     pub course_prefix: String,
     pub course_number: String,
     pub grade_letter: String,
     pub semester_code: String,
 }
 
-pub fn setup_database(conn: &mut PooledConn) -> Result<()> {
-    conn.query_drop(
-        r"CREATE TABLE IF NOT EXISTS Course (
-        Prefix nvarchar(5) not null,
-        Number nvarchar(5) not null,
-        Title nvarchar(32) not null,
-        Credits nvarchar(5) not null,
-        PRIMARY KEY (Prefix, Number))",
-    )?;
-    conn.query_drop(
-        r"CREATE TABLE IF NOT EXISTS Grade (
-        Letter nvarchar(5) not null,
-        Value nvarchar(5) not null,
-        PRIMARY KEY (Letter))",
-    )?;
-    conn.query_drop(
-        r"CREATE TABLE IF NOT EXISTS Semester (
-        Code nvarchar(4) not null,
-        Year nvarchar(4) not null,
-        Description varchar(6) not null,
-        PRIMARY KEY (Code))",
-    )?;
-    conn.query_drop(
-        r"CREATE TABLE IF NOT EXISTS Student (
-        LName nvarchar(15) not null,
-        FName nvarchar(15) not null,
-        Phone nvarchar(20) not null,
-        PRIMARY KEY (LName, FName))",
-    )?;
-    conn.query_drop(
-        r"CREATE TABLE IF NOT EXISTS TakenCourse (
-        StudentLName nvarchar(32) not null,
-        StudentFName nvarchar(32) not null,
-        CoursePrefix nvarchar(5) not null,
-        CourseNumber nvarchar(5) not null,
-        GradeLetter nvarchar(5) not null,
-        SemesterCode nvarchar(5) not null,
-        PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode))",
-    )?;
-    Ok(())
+/// One course on a student's transcript along with the semester it was taken and the grade received.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TranscriptRow {
+    pub semester: Semester,
+    pub course: Course,
+    pub grade: Grade,
+}
+
+/// How a student is picked out, either by ID or by last and first name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StudentKey {
+    Id(u32),
+    Name { lname: String, fname: String },
 }
 
 // Start insert functions
-/// Function to insert items into the database. Possible items to insert are courses, grades, semesters, students, and taken courses.
-pub fn insert_into_database(conn: &mut PooledConn, input: Vec<&str>) -> Result<String> {
-    let item = match input.get(1) {
-        Some(&"c") => insert_course(
-            conn,
-            Course {
-                prefix: match input.get(2) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Course needs a prefix.\n"
-                    )))
-                },
-                number: match input.get(3) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Course needs a number.\n"
-                    )))
-                },
-                title: match input.get(4) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Course needs a title.\n"
-                    )))
-                },
-                credits: match input.get(5) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Course needs number of credits.\n"
-                    )))
-                },
-            },
-        )?,
-        Some(&"g") => insert_grade(
-            conn,
-            Grade {
-                letter: match input.get(2) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Grade needs a type.\n"
-                    )))
-                },
-                value: match input.get(3) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Grade needs a point value.\n"
-                    )))
-                },
-            },
-        )?,
-        Some(&"m") => insert_semester(
-            conn,
-            Semester {
-                code: match input.get(2) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Semester needs a code.\n"
-                    )))
-                },
-                year: match input.get(3) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Semester needs a year.\n"
-                    )))
-                },
-                description: match input.get(4) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Semester needs a description.\n"
-                    )))
-                },
-            },
-        )?,
-        Some(&"s") => insert_student(
-            conn,
-            Student {
-                lname: match input.get(2) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Student needs a last name.\n"
-                    )))
-                },
-                fname: match input.get(3) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Student needs a first name.\n"
-                    )))
-                },
-                phone: match input.get(4) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Student needs a phone number.\n"
-                    )))
-                },
-            },
-        )?,
-        Some(&"t") => insert_taken_course(
-            conn,
-            TakenCourse {
-                student_lname: match input.get(2) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a student's last name.\n"
-                    )))
-                },
-                student_fname: match input.get(3) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a student's first name.\n"
-                    )))
-                },
-                course_prefix: match input.get(4) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a course's prefix.\n"
-                    )))
-                },
-                course_number: match input.get(5) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a course's number.\n"
-                    )))
-                },
-                grade_letter: match input.get(6) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a grade type.\n"
-                    )))
-                },
-                semester_code: match input.get(7) {
-                    Some(item) => item.to_string(),
-                    None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other, 
-                        "Error: Unable to insert item. Taken course needs a semester code.\n"
-                    )))
-                },
-            },
-        )?,
-        _ => return Err(mysql::Error::IoError(Error::new(
-            ErrorKind::Other, 
-            "Error: Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ester, (s)tudent, and (t)aken course.\n"
-        ))),
-    };
-    Ok(item)
+/// Adds a course to the database and returns it.
+pub fn add_course(store: &mut dyn RegistrarStore, course: Course) -> Result<Course> {
+    store.insert_course(&course)?;
+    Ok(course)
 }
 
-fn insert_course(conn: &mut PooledConn, course: Course) -> Result<String> {
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"INSERT INTO Course (Prefix, Number, Title, Credits)
-        VALUES (?, ?, ?, ?)",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(
-        stmt,
-        (
-            &course.prefix,
-            &course.number,
-            &course.title,
-            &course.credits,
-        ),
-    )?;
-    // Commit the transaction
-    transaction.commit()?;
-    // Send the OK! that the item was inserted
-    Ok(format!(
-        "{} {} {} {}",
-        course.prefix, course.number, course.title, course.credits
-    ))
+/// Adds a grade to the database and returns it. A grade that counts toward the GPA or earns credit
+/// has to count as attempted.
+pub fn add_grade(store: &mut dyn RegistrarStore, grade: Grade) -> Result<Grade> {
+    check_grade_flags(&grade, "Unable to insert item")?;
+    store.insert_grade(&grade)?;
+    Ok(grade)
 }
 
-fn insert_grade(conn: &mut PooledConn, grade: Grade) -> Result<String> {
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"INSERT INTO Grade (Letter, Value)
-        VALUES (?, ?)",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(stmt, (&grade.letter, &grade.value))?;
-    // Commit the transaction
-    transaction.commit()?;
-    // Send the OK! that the item was inserted
-    Ok(format!("{} {}", grade.letter, grade.value))
+/// Adds a semester to the database and returns it. The year has to be from 1 to 9999 and a semester
+/// cannot end before it starts.
+pub fn add_semester(store: &mut dyn RegistrarStore, semester: Semester) -> Result<Semester> {
+    check_year(&semester, "Unable to insert item")?;
+    check_dates(&semester, "Unable to insert item")?;
+    store.insert_semester(&semester)?;
+    Ok(semester)
 }
 
-fn insert_semester(conn: &mut PooledConn, semester: Semester) -> Result<String> {
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"INSERT INTO Semester (Code, Year, Description)
-        VALUES (?, ?, ?)",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(
-        stmt,
-        (&semester.code, &semester.year, &semester.description),
-    )?;
-    // Commit the transaction
-    transaction.commit()?;
-    // Send the OK! that the item was inserted
-    Ok(format!(
-        "{} {} {}",
-        semester.code, semester.year, semester.description
-    ))
+/// Adds a term to the database and returns it.
+pub fn add_term(store: &mut dyn RegistrarStore, term: Term) -> Result<Term> {
+    store.insert_term(&term)?;
+    Ok(term)
 }
 
-fn insert_student(conn: &mut PooledConn, student: Student) -> Result<String> {
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"INSERT INTO Student (LName, FName, Phone)
-        VALUES (?, ?, ?)",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(stmt, (&student.lname, &student.fname, &student.phone))?;
-    // Commit the transaction
-    transaction.commit()?;
-    // Send the OK! that the item was inserted
-    Ok(format!(
-        "{} {} {}",
-        student.lname, student.fname, student.phone
-    ))
+/// Adds a student to the database and returns them with the ID they were given. An ID of 0 lets the
+/// database pick the next one.
+pub fn add_student(store: &mut dyn RegistrarStore, student: Student) -> Result<Student> {
+    let id = store.insert_student(&student)?;
+    Ok(Student { id, ..student })
 }
 
-fn insert_taken_course(conn: &mut PooledConn, taken_course: TakenCourse) -> Result<String> {
-    // Check if the database contains the given student
-    if !search_student(conn, &taken_course)? {
-        return Err(mysql::Error::IoError(Error::new(
-            ErrorKind::Other,
-            "Error: Unable to add item to database. Given student does not exist.\n",
-        )));
-    // Check if the database contains the given course
-    } else if !search_course(conn, &taken_course)? {
-        return Err(mysql::Error::IoError(Error::new(
-            ErrorKind::Other,
-            "Error: Unable to add item to database. Given course does not exist.\n",
-        )));
-    // Check if the database contains the given grade
-    } else if !search_grade(conn, &taken_course)? {
-        return Err(mysql::Error::IoError(Error::new(
-            ErrorKind::Other,
-            "Error: Unable to add item to database. Given grade does not exist.\n",
-        )));
-    // Check if the databases contains the given semester
-    } else if !search_semester(conn, &taken_course)? {
-        return Err(mysql::Error::IoError(Error::new(
-            ErrorKind::Other,
-            "Error: Unable to add item to database. Given semester does not exist.\n",
-        )));
-    }
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"INSERT INTO TakenCourse (
-            StudentLName, 
-            StudentFName, 
-            CoursePrefix, 
-            CourseNumber, 
-            GradeLetter, 
-            SemesterCode)
-        VALUES (?, ?, ?, ?, ?, ?)",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(
-        stmt,
-        (
-            &taken_course.student_lname,
-            &taken_course.student_fname,
-            &taken_course.course_prefix,
-            &taken_course.course_number,
-            &taken_course.grade_letter,
-            &taken_course.semester_code,
-        ),
-    )?;
-    // Commit the transaction
-    transaction.commit()?;
-    // Send the OK! that the item was inserted
-    Ok(format!(
-        "{} {} {} {} {} {}",
-        taken_course.student_lname,
-        taken_course.student_fname,
-        taken_course.course_prefix,
-        taken_course.course_number,
-        taken_course.grade_letter,
-        taken_course.semester_code,
-    ))
+/// Adds a taken course to the database and returns it. The student, course, grade, and semester it
+/// names have to exist.
+pub fn add_taken_course(store: &mut dyn RegistrarStore, taken_course: TakenCourse) -> Result<TakenCourse> {
+    // The foreign keys on TakenCourse check that the student, course, grade, and semester exist
+    store.insert_taken_course(&taken_course)?;
+    Ok(taken_course)
 }
 
-fn search_student(conn: &mut PooledConn, taken_course: &TakenCourse) -> Result<bool> {
-    // Make a prepared statement
-    let stmt = conn.prep("SELECT * FROM Student WHERE Student.LName = ? AND Student.FName = ?")?;
-    // Execute query in prepared statement with given variables and store results in vector
-    let query = conn.exec_map(stmt,
-        (&taken_course.student_lname, &taken_course.student_fname), 
-        |(lname, fname, phone)|
-        Student {
-            lname,
-            fname, 
-            phone
-        }
-    )?;
-    // Check if any results were found
-    if query.is_empty() {
-        return Ok(false);
+/// Checks that a grade which counts toward the GPA or earns credit also counts as attempted.
+fn check_grade_flags(grade: &Grade, action: &str) -> Result<()> {
+    if (grade.counts_toward_gpa || grade.earns_credit) && !grade.attempted {
+        return Err(RegistrarError::Validation(format!(
+            "{action}. A grade that counts toward GPA or earns credit must count as attempted.",
+        )));
     }
-    Ok(true)
+    Ok(())
 }
 
-fn search_course(conn: &mut PooledConn, taken_course: &TakenCourse) -> Result<bool> {
-    // Make a prepared statement
-    let stmt = conn.prep("SELECT * FROM Course WHERE Course.Prefix = ? AND Course.Number = ?")?;
-    // Execute query in prepared statement with given variables and store results in vector
-    let query = conn.exec_map(stmt,
-        (&taken_course.course_prefix, &taken_course.course_number), 
-        |(prefix, number, title, credits)|
-        Course {
-            prefix,
-            number,
-            title,
-            credits
-        }
-    )?;
-    // Check if any results were found
-    if query.is_empty() {
-        return Ok(false);
+/// Checks that a semester's year is one every backend can store.
+fn check_year(semester: &Semester, action: &str) -> Result<()> {
+    if !(1..=9999).contains(&semester.year) {
+        return Err(RegistrarError::Validation(format!(
+            "{action}. Semester year must be from 1 to 9999.",
+        )));
     }
-    Ok(true)
+    Ok(())
 }
 
-fn search_grade(conn: &mut PooledConn, taken_course: &TakenCourse) -> Result<bool> {
-    // Make a prepared statement
-    let stmt = conn.prep("SELECT * FROM Grade WHERE Grade.Letter = ?")?;
-    // Execute query in prepared statement with given variables and store results in vector
-    let query = conn.exec_map(stmt,
-        (&taken_course.grade_letter,), 
-        |(letter, value)|
-        Grade {
-            letter,
-            value,
-        }
-    )?;
-    // Check if any results were found
-    if query.is_empty() {
-        return Ok(false);
+/// Checks that a semester does not end before it starts.
+fn check_dates(semester: &Semester, action: &str) -> Result<()> {
+    match (semester.start_date, semester.end_date) {
+        (Some(start), Some(end)) if end < start => Err(RegistrarError::Validation(format!(
+            "{action}. Semester cannot end before it starts.",
+        ))),
+        _ => Ok(()),
     }
-    Ok(true)
 }
 
-fn search_semester(conn: &mut PooledConn, taken_course: &TakenCourse) ->Result<bool> {
-    // Make a prepared statement
-    let stmt = conn.prep("SELECT * FROM Semester WHERE Semester.Code = ?")?;
-    // Execute query in prepared statement with given variables and store results in vector
-    let query = conn.exec_map(stmt,
-        (&taken_course.semester_code,), 
-        |(code, year, description)|
-        Semester {
-            code,
-            year,
-            description
-        }
-    )?;
-    // Check if any results were found
-    if query.is_empty() {
-        return Ok(false);
+/// Reads a date written as YYYY-MM-DD, or None if it is not a real date.
+pub fn date_from_str(item: &str) -> Option<Date> {
+    let parts: Vec<&str> = item.split('-').collect();
+    match parts[..] {
+        [year, month, day] => match (year.parse::<i32>(), month.parse::<u8>(), day.parse::<u8>()) {
+            (Ok(year), Ok(month), Ok(day)) => Month::try_from(month)
+                .ok()
+                .and_then(|month| Date::from_calendar_date(year, month, day).ok()),
+            _ => None,
+        },
+        _ => None,
     }
-    Ok(true)
 }
 // End insert functions
 
-// Start delete functions
-/// Function to delete items from the database. Possible items to delete are students.
-pub fn delete_from_database(conn: &mut PooledConn, input: Vec<&str>) -> Result<()> {
-    delete_student(
-        conn,
-        Student {
-            // Check if user actually entered something
-            lname: match input.get(2) {
-                Some(item) => item.to_string(),
-                None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other,
-                        "Error: Unable to delete student. No student supplied.\n",
-                )))
-            },
-            // Check if user actually entered something
-            fname: match input.get(3) {
-                Some(item) => item.to_string(),
-                None => return Err(mysql::Error::IoError(Error::new(
-                    ErrorKind::Other, 
-                    "Error: Unable to delete student. Student's first name not supplied.\n"
-                )))
-            },
-            phone: String::new(),
-        },
-    )?;
-    Ok(())
+// Start update functions
+/// Replaces the old course with the new one and returns it. Taken courses follow a new prefix or number.
+pub fn update_course(store: &mut dyn RegistrarStore, old: &Course, course: Course) -> Result<Course> {
+    // Taken courses follow a new prefix or number through the foreign key cascade
+    store.update_course(old, &course)?;
+    Ok(course)
 }
 
-fn delete_student(conn: &mut PooledConn, student: Student) -> Result<()> {
-    // Start a transaction
-    let mut transaction = conn.start_transaction(TxOpts::default())?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"DELETE FROM Student
-        WHERE Student.LName = ? AND Student.FName = ?",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(stmt, (&student.lname, &student.fname))?;
-    // Make a prepared statement
-    let stmt = transaction.prep(
-        r"DELETE FROM TakenCourse
-        WHERE TakenCourse.StudentLName = ? AND TakenCourse.StudentFName = ?",
-    )?;
-    // Execute query in prepared statement with given variables
-    transaction.exec_drop(stmt, (student.lname, student.fname))?;
-    // Commit the transaction
-    transaction.commit()
+/// Replaces the old grade with the new one and returns it.
+pub fn update_grade(store: &mut dyn RegistrarStore, old: &Grade, grade: Grade) -> Result<Grade> {
+    check_grade_flags(&grade, "Unable to edit item")?;
+    store.update_grade(old, &grade)?;
+    Ok(grade)
 }
-// End delete functions
-
 
-// Start list functions
-/// Function to list items from the database. Possible items to list are courses, grades, semesters, students, and taken courses.
-pub fn list_from_database(conn: &mut PooledConn, input: Vec<&str>) -> Result<()> {
-    match input.get(1) {
-        Some(&"c") => list_courses(conn)?,
-        Some(&"g") => list_grades(conn)?,
-        Some(&"m") => list_semesters(conn)?,
-        Some(&"s") => list_students(conn)?,
-        Some(&"t") => list_taken_courses(conn)?,
-        _ => println!("Error: Invalid subcommand. Valid subcommands are (c)ourse, (g)rade, se(m)ster, (s)tudent, (t)aken course.\n"),
-    }
-    Ok(())
+/// Replaces the old semester with the new one and returns it.
+pub fn update_semester(store: &mut dyn RegistrarStore, old: &Semester, semester: Semester) -> Result<Semester> {
+    check_year(&semester, "Unable to edit item")?;
+    check_dates(&semester, "Unable to edit item")?;
+    store.update_semester(old, &semester)?;
+    Ok(semester)
 }
 
-fn list_courses(conn: &mut PooledConn) -> Result<()> {
-    // Select all rows from table
-    let query = conn.query_map(
-        r"SELECT * FROM Course",
-        |(prefix, number, title, credits)|
-        Course {
-            prefix,
-            number,
-            title,
-            credits,
-        },
-    )?;
-    // Print out header for table
-    println!("+--------+--------+----------------------------------+---------+");
-    println!("| Prefix | Number | Title                            | Credits |");
-    println!("+--------+--------+----------------------------------+---------+");
-    // Iterate through rows from query and print out styled table
-    for item in query.iter() {
-        println!(
-            "| {:<6} | {:>6} | {:<32} | {:>7} |",
-            item.prefix, item.number, item.title, item.credits
-        );
-    }
-    println!("+--------+--------+----------------------------------+---------+\n");
-    Ok(())
+/// Replaces the old term with the new one and returns it. Semesters described by the old name are
+/// renamed along with it.
+pub fn update_term(store: &mut dyn RegistrarStore, old: &Term, term: Term) -> Result<Term> {
+    store.update_term(old, &term)?;
+    Ok(term)
 }
 
-fn list_grades(conn: &mut PooledConn) -> Result<()> {
-    // Select all rows from table
-    let query = conn.query_map(r"SELECT * FROM Grade", |(letter, value)| Grade {
-        letter,
-        value,
-    })?;
-    // Print out header for table
-    println!("+------+-------+");
-    println!("+ Type | Value |");
-    println!("+------+-------+");
-    // Iterate through rows from query and print out styled table
-    for item in query.iter() {
-        println!("| {:<4} | {:>5} |", item.letter, item.value);
-    }
-    println!("+------+-------+\n");
-    Ok(())
-}
-
-fn list_semesters(conn: &mut PooledConn) -> Result<()> {
-    // Select all rows from table
-    let query = conn.query_map(r"SELECT * FROM Semester", |(code, year, description)| {
-        Semester {
-            code,
-            year,
-            description,
-        }
-    })?;
-    // Print out header for table
-    println!("+------+------+--------+");
-    println!("| Code | Year | Desc   |");
-    println!("+------+------+--------+");
-    // Iterate through rows from query and print out styled table
-    for item in query.iter() {
-        println!("| {:<4} | {:>4} | {:<6} |", item.code, item.year, item.description);
-    }
-    println!("+------+------+--------+\n");
-    Ok(())
+/// Replaces the old student with the new one and returns them. Taken courses follow a new ID.
+pub fn update_student(store: &mut dyn RegistrarStore, old: &Student, student: Student) -> Result<Student> {
+    store.update_student(old, &student)?;
+    Ok(student)
 }
 
-fn list_students(conn: &mut PooledConn) -> Result<()> {
-    // Select all rows from table
-    let query = conn.query_map(r"SELECT * FROM Student", |(lname, fname, phone)| Student {
-        lname,
-        fname,
-        phone,
-    })?;
-    // Print out header for table
-    println!("+-----------------+-----------------+----------------------+");
-    println!("| Last Name       | First Name      | Phone Number         |");
-    println!("+-----------------+-----------------+----------------------+");
-    // Iterate through rows from query and print out styled table
-    for item in query.iter() {
-        println!("| {:<15} | {:<15} | {:>20} |", item.lname, item.fname, item.phone);
-    }
-    println!("+-----------------+-----------------+----------------------+\n");
-    Ok(())
+/// Replaces the old taken course with the new one and returns it.
+pub fn update_taken_course(
+    store: &mut dyn RegistrarStore,
+    old: &TakenCourse,
+    taken_course: TakenCourse,
+) -> Result<TakenCourse> {
+    // The foreign keys on TakenCourse check that the new student, course, grade, and semester exist
+    store.update_taken_course(old, &taken_course)?;
+    Ok(taken_course)
 }
+// End update functions
 
-fn list_taken_courses(conn: &mut PooledConn) -> Result<()> {
-    // Select all rows from table
-    let query = conn.query_map(
-        r"SELECT * FROM TakenCourse",
-        |(
-            student_lname,
-            student_fname,
-            course_prefix,
-            course_number,
-            grade_letter,
-            semester_code,
-        )| {
-            TakenCourse {
-                student_lname,
-                student_fname,
-                course_prefix,
-                course_number,
-                grade_letter,
-                semester_code,
+// Start lookup functions
+/// Finds the student with the given ID or name. Fails when more than one student has the name, naming
+/// their IDs so one of them can be picked.
+pub fn find_student(store: &mut dyn RegistrarStore, key: &StudentKey) -> Result<Student> {
+    let (lname, fname) = match key {
+        StudentKey::Id(id) => {
+            return match store.search_student(*id)? {
+                Some(student) => Ok(student),
+                None => Err(RegistrarError::NotFound(String::from("Given student does not exist."))),
             }
-        },
-    )?;
-    // Print out header for table
-    println!("+-----------------+-----------------+--------+--------+------+------+");
-    println!("| Last Name       | First Name      | Prefix | Number | Type | Code |");
-    println!("+-----------------+-----------------+--------+--------+------+------+");
-    // Iterate through rows from query and print out styled table
-    for item in query.iter() {
-        println!(
-            "| {:<15} | {:<15} | {:<6} | {:>6} | {:<4} | {:<4} |",
-            item.student_lname,
-            item.student_fname,
-            item.course_prefix,
-            item.course_number,
-            item.grade_letter,
-            item.semester_code
-        )
+        }
+        StudentKey::Name { lname, fname } => (lname, fname),
+    };
+    let mut students = store.search_students_by_name(lname, fname)?;
+    match students.len() {
+        0 => Err(RegistrarError::NotFound(String::from("Given student does not exist."))),
+        1 => Ok(students.remove(0)),
+        // Make the caller pick which of the students they meant
+        _ => {
+            let ids: Vec<String> = students.iter().map(|student| format!("#{}", student.id)).collect();
+            Err(RegistrarError::Validation(format!(
+                "More than one student is named {fname} {lname}. Use one of their IDs instead: {}.",
+                ids.join(", ")
+            )))
+        }
     }
-    println!("+-----------------+-----------------+--------+--------+------+------+\n");
-    Ok(())
 }
-// End list functions
 
-pub fn transcript(conn: &mut PooledConn, input: Vec<&str>) -> Result<()> {
-    print_transcript(conn, 
-        Student {
-            // Check if user actually entered something
-            lname: match input.get(1) {
-                Some(item) => item.to_string(),
-                None => return Err(mysql::Error::IoError(Error::new(
-                        ErrorKind::Other,
-                        "Error: Unable to print transcript. No student supplied.\n",
-                    )))
-            },
-            // Check if user actually entered something
-            fname: match input.get(2) {
-                Some(item) => item.to_string(),
-                None => return Err(mysql::Error::IoError(Error::new(
-                    ErrorKind::Other, 
-                    "Error: Unable to print transcript. Student's first name not supplied.\n"
-                    )))
-            },
-            phone: String::new(),
-        },
-    )?;
-    Ok(())
-}
-
-fn print_transcript(conn: &mut PooledConn, student: Student) -> Result<()> {
-    let stmt = conn.prep(
-        r"SELECT Semester.Description, Semester.Year, Course.Prefix, Course.Number, 
-        Course.Title, Course.Credits, Grade.Letter, Grade.Value
-        FROM TakenCourse, Semester, Course, Grade
-        WHERE TakenCourse.StudentLName = ? AND TakenCourse.StudentFName = ? 
-        AND TakenCourse.CoursePrefix = Course.Prefix AND TakenCourse.CourseNumber = Course.Number 
-        AND TakenCourse.GradeLetter = Grade.Letter AND TakenCourse.SemesterCode = Semester.Code
-        GROUP BY Semester.Code"
-    )?;
-    let taken_courses = conn.exec_map(stmt, 
-        (&student.lname, &student.fname), 
-        |(student_lname, student_fname, course_prefix, course_number, grade_letter, semester_code)| 
-        TakenCourse {
-            student_lname,
-            student_fname,
-            course_prefix,
-            course_number,
-            grade_letter,
-            semester_code,
-        }
-    )?;
-    if taken_courses.is_empty() {
-        println!("Error: Unable to print transcript. Given student either does not exist or has not taken and courses yet.\n");
-        return Ok(());
-    }
-    let mut courses: Vec<Course> = Vec::new();
-    let mut grades: Vec<Grade> = Vec::new();
-    let mut semesters: Vec<Semester> = Vec::new();
-    for taken_course in taken_courses.iter() {
-        courses.append(&mut conn.exec_map(r"SELECT * FROM Course WHERE Course.Prefix = ? AND Course.Number = ?", 
-            (&taken_course.course_prefix, &taken_course.course_number), 
-            |(prefix, number, title, credits)| Course {prefix, number, title, credits})?);
-        grades.append(&mut conn.exec_map(r"SELECT * FROM Grade WHERE Grade.Letter = ?", 
-            (&taken_course.grade_letter,), 
-            |(letter, value)| Grade {letter, value})?);
-        semesters.append(&mut conn.exec_map(r"SELECT * FROM Semester WHERE Semester.Code = ?", 
-        (&taken_course.semester_code,), 
-            |(code, year, description)| Semester {code, year, description})?);
-    }
-    semesters.sort();
-    let mut sum_credits = 0;
-    let mut sum_grade_value = 0.0;
-    let num_courses = taken_courses.len() as f32;
-    for semester in semesters.iter() {
-        println!("============ Semester: {:<6} {} ============", semester.description, semester.year);
-        for taken_course in taken_courses.iter() {
-            if taken_course.semester_code == semester.code {
-                for course in courses.iter() {
-                    if course.prefix == taken_course.course_prefix && course.number == taken_course.course_number {
-                        for grade in grades.iter() {
-                            if grade.letter == taken_course.grade_letter {
-                                sum_credits = sum_credits + course.credits.parse::<i32>().unwrap();
-                                sum_grade_value = sum_grade_value + grade.value.parse::<f32>().unwrap();
-                                println!("{}{} {} ({}) {}", course.prefix, course.number, course.title, course.credits, taken_course.grade_letter);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+/// Finds the student's taken course for the given course and semester. The grade type only has to
+/// be given when the student was graded more than once for the course that semester.
+pub fn find_taken_course(
+    store: &mut dyn RegistrarStore,
+    student: &Student,
+    prefix: &str,
+    number: &str,
+    code: &str,
+    letter: Option<&str>,
+) -> Result<TakenCourse> {
+    let mut matches: Vec<TakenCourse> = store
+        .dependent_taken_courses(Referenced::Student(student))?
+        .into_iter()
+        .filter(|item| {
+            item.course_prefix == prefix
+                && item.course_number == number
+                && item.semester_code == code
+                && letter.is_none_or(|letter| item.grade_letter == letter)
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(RegistrarError::NotFound(String::from("Given taken course does not exist."))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(RegistrarError::Validation(String::from(
+            "Student has more than one grade for that course that semester. Give the grade type after the semester code.",
+        ))),
     }
-    println!("  STUDENT HOURS COMPLETED: {sum_credits}");
-    println!("  STUDENT GPA: {}\n", sum_grade_value/num_courses);
-    Ok(())
-}
\ No newline at end of file
+}
+// End lookup functions
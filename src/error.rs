@@ -0,0 +1,89 @@
+// Author: Grant Duchars
+use std::fmt;
+
+/// Everything that can keep a command from being carried out. Each kind holds the message shown to
+/// the user, which Display prefixes with "Error: ".
+#[derive(Debug)]
+pub enum RegistrarError {
+    /// The input was not valid, like a missing argument or a number out of range.
+    Validation(String),
+    /// The item the command works on does not exist.
+    NotFound(String),
+    /// The item being saved has the same key as one already in the database.
+    Duplicate(String),
+    /// The change would break a reference between items, like deleting a course that was taken.
+    Constraint(String),
+    /// The database could not be reached or the connection to it was lost.
+    Connection(String),
+    /// Anything else the database or the program ran into.
+    Internal(String),
+}
+
+pub type Result<T> = std::result::Result<T, RegistrarError>;
+
+impl RegistrarError {
+    pub fn message(&self) -> &str {
+        match self {
+            RegistrarError::Validation(message)
+            | RegistrarError::NotFound(message)
+            | RegistrarError::Duplicate(message)
+            | RegistrarError::Constraint(message)
+            | RegistrarError::Connection(message)
+            | RegistrarError::Internal(message) => message,
+        }
+    }
+
+    /// Puts what was being done in front of the message, keeping the same kind of error.
+    pub fn during(self, action: &str) -> RegistrarError {
+        let message = format!("{action}. {}", self.message());
+        match self {
+            RegistrarError::Validation(_) => RegistrarError::Validation(message),
+            RegistrarError::NotFound(_) => RegistrarError::NotFound(message),
+            RegistrarError::Duplicate(_) => RegistrarError::Duplicate(message),
+            RegistrarError::Constraint(_) => RegistrarError::Constraint(message),
+            RegistrarError::Connection(_) => RegistrarError::Connection(message),
+            RegistrarError::Internal(_) => RegistrarError::Internal(message),
+        }
+    }
+}
+
+impl fmt::Display for RegistrarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error: {}", self.message())
+    }
+}
+
+impl std::error::Error for RegistrarError {}
+
+/// Reading from the terminal or a file failed.
+impl From<std::io::Error> for RegistrarError {
+    fn from(error: std::io::Error) -> RegistrarError {
+        RegistrarError::Internal(error.to_string())
+    }
+}
+
+/// Sorts the errors the mysql crate gives by what went wrong. Other backends convert their own
+/// errors in their store modules.
+impl From<mysql::Error> for RegistrarError {
+    fn from(error: mysql::Error) -> RegistrarError {
+        match error {
+            // ER_DUP_ENTRY
+            mysql::Error::MySqlError(ref my_sql_error) if my_sql_error.code == 1062 => {
+                RegistrarError::Duplicate(my_sql_error.message.clone())
+            }
+            // ER_ROW_IS_REFERENCED_2 and ER_NO_REFERENCED_ROW_2
+            mysql::Error::MySqlError(ref my_sql_error) if my_sql_error.code == 1451 || my_sql_error.code == 1452 => {
+                RegistrarError::Constraint(my_sql_error.message.clone())
+            }
+            // ER_SERVER_SHUTDOWN, ER_CONNECTION_KILLED and ER_CLIENT_INTERACTION_TIMEOUT are sent
+            // just before the server closes the connection
+            mysql::Error::MySqlError(ref my_sql_error) if matches!(my_sql_error.code, 1053 | 1927 | 4031) => {
+                RegistrarError::Connection(my_sql_error.message.clone())
+            }
+            mysql::Error::IoError(_)
+            | mysql::Error::DriverError(mysql::DriverError::CouldNotConnect(_))
+            | mysql::Error::UrlError(_) => RegistrarError::Connection(error.to_string()),
+            _ => RegistrarError::Internal(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+// Author: Grant Duchars
+use registrar::error::{RegistrarError, Result};
+use registrar::store::RegistrarStore;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Splits a line typed at the prompt into its arguments the way a shell would. Arguments are
+/// separated by any amount of whitespace. Single quotes keep everything inside them as is, double
+/// quotes do the same except that \" and \\ are escapes, and outside of quotes a backslash keeps the
+/// next character as part of the argument. So "Intro to Databases", 'Van Buren' and Van\ Buren are
+/// all single arguments, and "" is an empty one.
+pub fn split_input(line: &str) -> Result<Vec<String>> {
+    let scanned = scan(line);
+    match scanned.unfinished {
+        Some(problem) => Err(RegistrarError::Validation(format!("Invalid input. {problem}."))),
+        None => Ok(scanned.args),
+    }
+}
+
+/// The arguments found in a line, along with what is needed to complete the last one.
+struct Scanned {
+    args: Vec<String>,
+    /// Where each argument starts in the line, counting its opening quote.
+    starts: Vec<usize>,
+    /// Whether the line ends inside the last argument instead of after it.
+    in_arg: bool,
+    /// What is missing if the line ends in the middle of a quote or escape.
+    unfinished: Option<&'static str>,
+}
+
+fn scan(line: &str) -> Scanned {
+    let mut scanned = Scanned { args: Vec::new(), starts: Vec::new(), in_arg: false, unfinished: None };
+    // The argument being built, which is None between arguments so that "" can still be one
+    let mut arg: Option<String> = None;
+    let mut chars = line.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if arg.is_none() && !c.is_whitespace() {
+            scanned.starts.push(index);
+        }
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = arg.take() {
+                    scanned.args.push(arg);
+                }
+            }
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => arg.push(c),
+                        None => {
+                            scanned.unfinished = Some("Missing closing ' quote");
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c @ ('"' | '\\'))) => arg.push(c),
+                            // Other backslashes inside double quotes are kept like in a shell
+                            Some((_, c)) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => {
+                                scanned.unfinished = Some("Missing closing \" quote");
+                                break;
+                            }
+                        },
+                        Some((_, c)) => arg.push(c),
+                        None => {
+                            scanned.unfinished = Some("Missing closing \" quote");
+                            break;
+                        }
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some((_, c)) => arg.get_or_insert_with(String::new).push(c),
+                None => scanned.unfinished = Some("Nothing to escape after the last \\"),
+            },
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(arg) = arg {
+        scanned.args.push(arg);
+        scanned.in_arg = true;
+    }
+    scanned
+}
+
+/// Quotes an argument so that split_input reads it back as one argument.
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '\\')) {
+        arg.to_string()
+    } else if !arg.contains('\'') {
+        format!("'{arg}'")
+    } else {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+// Start completion functions
+
+const COMMANDS: [&str; 7] = ["add", "delete", "edit", "list", "transcript", "migrate", "q"];
+const ITEMS: [&str; 6] = ["course", "grade", "semester", "term", "student", "taken"];
+const LIST_ITEMS: [&str; 6] = ["courses", "grades", "semesters", "terms", "students", "taken"];
+const MIGRATE_ACTIONS: [&str; 3] = ["status", "up", "down"];
+
+/// The keys already in the database that can be completed. They are loaded when the prompt starts
+/// and again after each command that changes the database so new items show up.
+#[derive(Default)]
+pub struct Keys {
+    courses: Vec<(String, String)>,
+    grades: Vec<String>,
+    semesters: Vec<String>,
+    terms: Vec<String>,
+    students: Vec<(u32, String, String)>,
+}
+
+impl Keys {
+    pub fn load(store: &mut dyn RegistrarStore) -> Result<Keys> {
+        Ok(Keys {
+            courses: store.list_courses()?.into_iter().map(|course| (course.prefix, course.number)).collect(),
+            grades: store.list_grades()?.into_iter().map(|grade| grade.letter).collect(),
+            semesters: store.list_semesters()?.into_iter().map(|semester| semester.code).collect(),
+            terms: store.list_terms()?.into_iter().map(|term| term.name).collect(),
+            students: store
+                .list_students()?
+                .into_iter()
+                .map(|student| (student.id, student.lname, student.fname))
+                .collect(),
+        })
+    }
+}
+
+/// The kinds of keys that commands take, in the order they take them.
+#[derive(Clone, Copy)]
+enum Slot {
+    Prefix,
+    Number,
+    Grade,
+    Semester,
+    Term,
+    /// Either a #ID or a last and first name.
+    Student,
+    /// Something new that cannot be completed, like the title of a course being added.
+    New,
+}
+
+/// What the argument being completed should be.
+enum Wanted<'a> {
+    Commands,
+    Items(&'static [&'static str]),
+    Prefix,
+    Number(&'a str),
+    Grade,
+    Semester,
+    Term,
+    LastName,
+    FirstName(&'a str),
+    Fields(&'static [&'static str]),
+    Nothing,
+}
+
+/// Tab completion for the prompt. Completes commands, items, edit fields and flags, and the keys of
+/// courses, grades, semesters, terms and students already in the database.
+#[derive(Default)]
+pub struct RegistrarHelper {
+    pub keys: Keys,
+}
+
+impl RegistrarHelper {
+    /// Works out what the next argument should be from the arguments before it.
+    fn wanted<'a>(&self, args: &[&'a str]) -> Wanted<'a> {
+        let command = match args.first() {
+            Some(command) => expand(command),
+            None => return Wanted::Commands,
+        };
+        let item = args.get(1).map(|item| expand(item)).unwrap_or("");
+        let (slots, fields, first): (&[Slot], &'static [&'static str], usize) = match (command, item) {
+            ("a" | "d" | "e" | "l", _) if args.len() == 1 => {
+                return Wanted::Items(if command == "l" { &LIST_ITEMS } else { &ITEMS })
+            }
+            ("migrate", _) if args.len() == 1 => return Wanted::Items(&MIGRATE_ACTIONS),
+            ("a", "c") => (&[Slot::Prefix], &[], 2),
+            ("a", "m") => (&[Slot::New, Slot::New, Slot::Term], &[], 2),
+            ("a", "t") => (&[Slot::Student, Slot::Prefix, Slot::Number, Slot::Grade, Slot::Semester], &[], 2),
+            ("d" | "e", "c") => (&[Slot::Prefix, Slot::Number], &["prefix=", "number=", "title=", "credits="], 2),
+            ("d" | "e", "g") => (&[Slot::Grade], &["type=", "value=", "gpa=", "credit=", "attempted="], 2),
+            ("d" | "e", "m") => (&[Slot::Semester], &["code=", "year=", "desc=", "start=", "end="], 2),
+            ("d" | "e", "r") => (&[Slot::Term], &["name=", "sequence="], 2),
+            ("d" | "e", "s") => (&[Slot::Student], &["id=", "lname=", "fname=", "phone="], 2),
+            ("d" | "e", "t") => (
+                &[Slot::Student, Slot::Prefix, Slot::Number, Slot::Semester, Slot::Grade],
+                &["student=", "prefix=", "number=", "type=", "code="],
+                2,
+            ),
+            ("t", _) => (&[Slot::Student], &[], 1),
+            _ => return Wanted::Nothing,
+        };
+        // Walk the slots along the arguments already given to find the one being completed
+        let mut index = first;
+        for slot in slots {
+            let wanted = match slot {
+                Slot::Student => match args.get(index) {
+                    None => Wanted::LastName,
+                    Some(id) if id.starts_with('#') => {
+                        index += 1;
+                        continue;
+                    }
+                    Some(lname) => match args.get(index + 1) {
+                        None => Wanted::FirstName(lname),
+                        Some(_) => {
+                            index += 2;
+                            continue;
+                        }
+                    },
+                },
+                _ if index < args.len() => {
+                    index += 1;
+                    continue;
+                }
+                Slot::Prefix => Wanted::Prefix,
+                Slot::Number => Wanted::Number(args[index - 1]),
+                Slot::Grade => Wanted::Grade,
+                Slot::Semester => Wanted::Semester,
+                Slot::Term => Wanted::Term,
+                Slot::New => Wanted::Nothing,
+            };
+            return wanted;
+        }
+        // Edits end with the changes to make
+        if command == "e" {
+            Wanted::Fields(fields)
+        } else {
+            Wanted::Nothing
+        }
+    }
+
+    /// Lists everything that could go where the argument being completed is.
+    fn candidates(&self, wanted: Wanted, word: &str) -> Vec<String> {
+        let keys = &self.keys;
+        let mut candidates: Vec<String> = match wanted {
+            Wanted::Commands => COMMANDS.iter().map(|command| command.to_string()).collect(),
+            Wanted::Items(items) | Wanted::Fields(items) => items.iter().map(|item| item.to_string()).collect(),
+            Wanted::Prefix => keys.courses.iter().map(|(prefix, _)| prefix.clone()).collect(),
+            Wanted::Number(prefix) => keys
+                .courses
+                .iter()
+                .filter(|(item, _)| item == prefix)
+                .map(|(_, number)| number.clone())
+                .collect(),
+            Wanted::Grade => keys.grades.clone(),
+            Wanted::Semester => keys.semesters.clone(),
+            Wanted::Term => keys.terms.clone(),
+            // Students can be given by ID as well as by name
+            Wanted::LastName if word.starts_with('#') => {
+                keys.students.iter().map(|(id, _, _)| format!("#{id}")).collect()
+            }
+            Wanted::LastName => keys.students.iter().map(|(_, lname, _)| lname.clone()).collect(),
+            Wanted::FirstName(lname) => keys
+                .students
+                .iter()
+                .filter(|(_, item, _)| item == lname)
+                .map(|(_, _, fname)| fname.clone())
+                .collect(),
+            Wanted::Nothing => Vec::new(),
+        };
+        candidates.retain(|candidate| candidate.starts_with(word));
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// Flags each command takes, which are completed wherever an argument starts with a dash.
+fn flags(command: &str, item: &str) -> &'static [&'static str] {
+    match (command, item) {
+        ("a", "g") => &["--no-gpa", "--credit", "--no-credit", "--not-attempted"],
+        ("d", _) => &["--cascade", "--dry-run"],
+        ("t", _) => &[
+            "--round=",
+            "--truncate=",
+            "--repeat=replace",
+            "--repeat=average",
+            "--repeat=highest",
+            "--format=text",
+            "--format=table",
+            "--format=csv",
+            "--format=tsv",
+            "--format=json",
+            "--format=jsonl",
+            "--format=markdown",
+            "--format=html",
+        ],
+        ("l", _) => &["--format=table", "--format=csv", "--format=tsv", "--format=json", "--format=jsonl"],
+        _ => &[],
+    }
+}
+
+/// Swaps the full names of commands and items for their letters, like the commands themselves do.
+fn expand(name: &str) -> &str {
+    match name {
+        "add" => "a",
+        "delete" => "d",
+        "edit" => "e",
+        "list" => "l",
+        "transcript" => "t",
+        "course" | "courses" => "c",
+        "grade" | "grades" => "g",
+        "semester" | "semesters" => "m",
+        "term" | "terms" => "r",
+        "student" | "students" => "s",
+        "taken" | "taken-course" | "taken-courses" => "t",
+        name => name,
+    }
+}
+
+impl Completer for RegistrarHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let scanned = scan(&line[..pos]);
+        // The argument under the cursor is either the last one or a new empty one after it
+        let (args, word, start) = match scanned.in_arg {
+            true => {
+                let last = scanned.args.len() - 1;
+                (&scanned.args[..last], scanned.args[last].as_str(), scanned.starts[last])
+            }
+            false => (&scanned.args[..], "", pos),
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let candidates = if word.starts_with('-') {
+            let command = args.first().map(|command| expand(command)).unwrap_or("");
+            let item = args.get(1).map(|item| expand(item)).unwrap_or("");
+            flags(command, item).iter().filter(|flag| flag.starts_with(word)).map(|flag| flag.to_string()).collect()
+        } else {
+            // Flags can go anywhere, so they are left out when working out the argument's place
+            let args: Vec<&str> = args.into_iter().filter(|arg| !arg.starts_with("--")).collect();
+            self.candidates(self.wanted(&args), word)
+        };
+        let pairs = candidates
+            .into_iter()
+            .map(|candidate| Pair {
+                // Finished arguments are followed by a space, but fields and flags still need a value
+                replacement: match candidate.ends_with('=') {
+                    true => quote(&candidate),
+                    false => format!("{} ", quote(&candidate)),
+                },
+                display: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for RegistrarHelper {
+    type Hint = String;
+}
+
+impl Highlighter for RegistrarHelper {}
+
+impl Validator for RegistrarHelper {}
+
+impl Helper for RegistrarHelper {}
+
+// End completion functions
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Vec<String> {
+        split_input(line).unwrap()
+    }
+
+    #[test]
+    fn whitespace_separates_arguments() {
+        assert_eq!(split("  a   c\tCSC 300 "), vec!["a", "c", "CSC", "300"]);
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes_and_backslashes_keep_spaces() {
+        assert_eq!(split(r#"a c CSC 150 "Intro to CS" 4"#), vec!["a", "c", "CSC", "150", "Intro to CS", "4"]);
+        assert_eq!(split("a s 'Van Buren' Martin"), vec!["a", "s", "Van Buren", "Martin"]);
+        assert_eq!(split(r"Van\ Buren"), vec!["Van Buren"]);
+        // Quoted and unquoted parts next to each other make one argument
+        assert_eq!(split(r#"O"'"Brien"#), vec!["O'Brien"]);
+    }
+
+    #[test]
+    fn escapes_inside_double_quotes() {
+        assert_eq!(split(r#""say \"hi\"" "a\\b" "c\d""#), vec![r#"say "hi""#, r"a\b", r"c\d"]);
+        // Backslashes are kept as is inside single quotes
+        assert_eq!(split(r"'a\b'"), vec![r"a\b"]);
+    }
+
+    #[test]
+    fn empty_quotes_are_an_argument() {
+        assert_eq!(split(r#"e s #1 phone="" """#), vec!["e", "s", "#1", "phone=", ""]);
+        assert_eq!(split("''"), vec![""]);
+    }
+
+    #[test]
+    fn unfinished_quotes_and_escapes_are_errors() {
+        for line in [r#"a c "Intro"#, "a s 'Van", "a s Van\\"] {
+            assert!(matches!(split_input(line), Err(RegistrarError::Validation(_))), "{line}");
+        }
+    }
+
+    #[test]
+    fn quoted_arguments_read_back_the_same() {
+        for arg in ["plain", "two words", "it's", r#"say "hi" \ there"#, ""] {
+            assert_eq!(split(&quote(arg)), vec![arg]);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// Author: Grant Duchars
+//! The registrar's courses, grades, semesters, terms, students and taken courses, along with the
+//! databases they are kept in. The functions in dbmgmt add, change and look up items, returning the
+//! data instead of printing it so other programs can use them, and transcript::Transcript works out a
+//! student's transcript for any of its renderers. Items are read and deleted through the
+//! RegistrarStore trait, and store::open picks the backend from a url.
+pub mod config;
+pub mod dbmgmt;
+pub mod error;
+pub mod memory_store;
+pub mod migrations;
+pub mod output;
+pub mod mysql_store;
+pub mod postgres_store;
+pub mod sqlite_store;
+pub mod store;
+pub mod transcript;
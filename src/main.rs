@@ -1,75 +1,386 @@
 // Author: Grant Duchars
-mod dbmgmt;
-use dbmgmt::*;
-use mysql::*;
+mod commands;
+mod input;
+use commands::*;
+use input::{split_input, Keys, RegistrarHelper};
+use mysql::OptsBuilder;
+use registrar::config::{self, Profile};
+use registrar::error::{RegistrarError, Result};
+use registrar::migrations::migrate_up;
+use registrar::mysql_store::MySqlStore;
+use registrar::store::{self, RegistrarStore};
 use rpassword::prompt_password;
-use std::io::{stdin, stdout, ErrorKind, Write};
+use rustyline::error::ReadlineError;
+use rustyline::{CompletionType, Config, Editor};
+use std::env;
+use std::io::{stderr, stdin, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process;
 
-// Need to implement:
-// Transcript
-// Quit
+/// How to run the program, shown by the help command.
+const USAGE: &str = "Usage: registrar [--config=PATH] [--profile=NAME] [DATABASE_URL] [COMMAND [ARGS...]]
+
+With no command an interactive prompt is started. Otherwise the command is run once and the
+program exits with status 0 if it succeeded, 1 if it failed and 2 if it was not understood.
+
+Commands:
+  add ITEM ARGS...                  Add a course, grade, semester, term, student or taken course
+  delete ITEM KEY... [--cascade] [--dry-run]
+  edit ITEM KEY... FIELD=VALUE...
+  list ITEM [--format=FORMAT]       ITEM is courses, grades, semesters, terms, students or taken
+  transcript STUDENT [--round=N] [--truncate=N] [--repeat=POLICY] [--format=FORMAT]
+  migrate status|up|down [VERSION]
+  help
+
+FORMAT is table (the default), csv, tsv, json or jsonl for lists. Transcripts take the same formats
+along with text (the default), markdown and html. Options and flags that take a value can be given
+as --flag=VALUE or --flag VALUE.
+
+DATABASE_URL is mysql://, postgres://, sqlite://PATH or memory://, or the path of a SQLite file
+that already exists. Without one the url or MySQL connection info comes from the DATABASE_URL
+and REGISTRAR_* environment variables and the profile in the config file (registrar.toml unless
+--config or REGISTRAR_CONFIG says otherwise), and anything still missing is prompted for. MySQL
+TLS is set up with the require_ssl and ssl_* profile settings or the matching REGISTRAR_*SSL*
+variables.";
+
+/// Names of the commands that can be given on the command line instead of a database url. Quitting
+/// only makes sense at the prompt, so q is left out.
+const COMMANDS: [&str; 14] = [
+    "a", "add", "d", "delete", "e", "edit", "l", "list", "t", "transcript", "migrate", "help", "-h", "--help",
+];
 
 fn main() {
-    // Get mysql connection info from user
-    let hostname = prompt_input("MySQL Hostname: ").unwrap();
-    let port_num = prompt_input("MySQL Port Num: ").unwrap();
-    let username = prompt_input("MySQL Username: ").unwrap();
-    let password = prompt_password("MySQL Password: ").unwrap();
-    let database = prompt_input("MySQL Database: ").unwrap();
-
-    // Connect to mysql server using connection info
-    let url = format!("mysql://{username}:{password}@{hostname}:{port_num}/{database}");
-    let pool = Pool::new(url.as_str()).unwrap();
-    let mut conn = pool.get_conn().unwrap();
+    // Pull out the options picking the config file and profile, given as --config=PATH or --config PATH
+    let mut options = Vec::new();
+    let mut args = Vec::new();
+    let mut items = env::args().skip(1);
+    while let Some(arg) = items.next() {
+        if arg == "--config" || arg == "--profile" {
+            options.push(format!("{arg}={}", items.next().unwrap_or_default()));
+        } else if arg.starts_with("--config=") || arg.starts_with("--profile=") {
+            options.push(arg);
+        } else {
+            args.push(arg);
+        }
+    }
+    let option = |name: &str| options.iter().rev().find_map(|arg| arg.strip_prefix(name));
+    // The first argument is the database url unless it is already a command. Anything else has to
+    // look like a url so a mistyped command is not taken for a new SQLite file.
+    let (url, command) = match args.first() {
+        Some(first) if !COMMANDS.contains(&first.as_str()) => {
+            if !store::is_url(first) {
+                eprintln!("Error: Invalid command or database url '{first}'. New SQLite files are given as sqlite://PATH.\n\n{USAGE}");
+                process::exit(2);
+            }
+            (Some(first.as_str()), &args[1..])
+        }
+        _ => (None, &args[..]),
+    };
+    let interactive = command.is_empty();
+    if matches!(command.first().map(String::as_str), Some("help" | "-h" | "--help")) {
+        println!("{USAGE}");
+        return;
+    }
 
-    // Notify user that the connection has been made
-    println!("\nYou are now connected to '{hostname}' using '{database}' database.\n");
+    // Load the connection settings from the config file and environment
+    let settings = match config::load(option("--config="), option("--profile=")) {
+        Ok(settings) => settings,
+        Err(error) => exit_with(error),
+    };
+    // Pick the database backend from the connection url given on the command line, or else the one
+    // from the settings. With no url at all the missing MySQL connection info is prompted for.
+    let opened = match url.or(settings.url.as_deref()) {
+        Some(url) => open_store(url, &settings),
+        None => connect_mysql(&settings),
+    };
+    let (mut store, notice) = match opened {
+        Ok(opened) => opened,
+        Err(error) => exit_with(error),
+    };
+    let store = store.as_mut();
+    // Keep standard output clean for scripts by sending notices to standard error
+    let notify = |message: &str| {
+        if interactive {
+            println!("{message}");
+        } else {
+            eprintln!("{}", message.trim());
+        }
+    };
+    notify(&notice);
+
+    // Bring the database schema up to date by applying any pending migrations
+    let applied = match migrate_up(store, None) {
+        Ok(applied) => applied,
+        Err(error) => exit_with(error),
+    };
+    for migration in applied.iter() {
+        notify(&format!("Applied migration {}: {}", migration.version, migration.description));
+    }
+    if interactive && !applied.is_empty() {
+        println!();
+    }
+
+    // Run the one command given on the command line and exit with its status
+    if !interactive {
+        let input: Vec<&str> = command.iter().map(String::as_str).collect();
+        let status = match run_command(store, input) {
+            Ok(()) => 0,
+            Err(Failure::Invalid(error)) => {
+                eprintln!("{error}\n\n{USAGE}");
+                2
+            }
+            Err(Failure::Failed(error)) => {
+                eprintln!("{error}");
+                1
+            }
+        };
+        process::exit(status);
+    }
+
+    // Set up line editing with tab completion and the history from earlier sessions
+    let config = Config::builder()
+        .auto_add_history(true)
+        .max_history_size(1000)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor = match Editor::<RegistrarHelper>::with_config(config) {
+        Ok(editor) => editor,
+        Err(error) => exit_with(RegistrarError::Internal(format!("Unable to set up the prompt. {error}"))),
+    };
+    editor.set_helper(Some(RegistrarHelper::default()));
+    let history = history_path();
+    if let Some(path) = &history {
+        // There is no history to load the first time the program is run
+        let _ = editor.load_history(path);
+    }
 
-    // Create the neccessary database tables if they do not exist
-    setup_database(&mut conn).unwrap();
+    // Load the keys once and keep them until a command changes the database
+    let mut refresh_keys = true;
 
-    // Main loop. Prompts user for input and terminates when user inputs "q"
+    // Main loop. Prompts user for input and terminates when user inputs "q" or ends the input
     loop {
-        let input = prompt_input(">>> ").unwrap();
-        let input: Vec<&str> = input.split(" ").collect();
-        // Check what command the user wants to run
-        match input[0] {
-            "a" => {
-                // Run the insert and check if any mysql errors were thrown
-                match insert_into_database(&mut conn, input) {
-                    Err(e) => {
-                        match e {
-                            mysql::Error::MySqlError(my_sql_error) => {
-                                if my_sql_error.code == 1062 {
-                                    println!("Error: Unable to add item to database. Duplicate entry found.\n");
-                                }
-                            }
-                            mysql::Error::IoError(error) => {
-                                if error.kind() == ErrorKind::Other {
-                                    println!("{error}");
-                                }
-                            },
-                            _ => panic!(),
-                        }
-                    },
-                    Ok(item) => println!("Successfully inserted '{item}' into database.\n")
-                }
+        if refresh_keys {
+            // Completion keeps the old keys if they cannot be loaded
+            if let (Some(helper), Ok(keys)) = (editor.helper_mut(), Keys::load(store)) {
+                helper.keys = keys;
             }
-            "d" => delete_from_database(&mut conn, input).unwrap(),
-            "l" => list_from_database(&mut conn, input).unwrap(),
-            "t" => transcript(&mut conn, input).unwrap(),
-            "q" => return,
-            _ => println!("Error: Invalid command. Valid commands are (a)dd, (d)elete, (l)ist, (t)ranscript, (q)uit.\n"),
+            refresh_keys = false;
+        }
+        let line = match editor.readline(">>> ") {
+            Ok(line) => line,
+            // Ctrl-C throws away the line being typed
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => {
+                eprintln!("Error: Unable to read input. {error}");
+                break;
+            }
+        };
+        // Split the line like a shell would so arguments with spaces can be quoted
+        let args = match split_input(&line) {
+            Ok(args) => args,
+            Err(error) => {
+                println!("{error}\n");
+                continue;
+            }
+        };
+        let input: Vec<&str> = args.iter().map(String::as_str).collect();
+        // Ignore blank lines
+        if input.is_empty() {
+            continue;
+        }
+        if input[0] == "q" {
+            break;
+        }
+        // Only commands that change the database can change the keys completion offers
+        let changes = matches!(input[0], "a" | "add" | "d" | "delete" | "e" | "edit" | "migrate");
+        match run_command(store, input) {
+            Ok(()) => refresh_keys = changes,
+            Err(Failure::Invalid(error)) | Err(Failure::Failed(error)) => println!("{error}\n"),
+        }
+    }
+    if let Some(path) = &history {
+        if let Err(error) = editor.save_history(path) {
+            eprintln!("Warning: Unable to save history to '{}'. {error}", path.display());
         }
     }
 }
 
+/// The file the prompt's history is kept in between sessions, which is REGISTRAR_HISTORY or else
+/// .registrar_history in the home directory.
+fn history_path() -> Option<PathBuf> {
+    match env::var_os("REGISTRAR_HISTORY") {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".registrar_history")),
+    }
+}
+
+/// Why a command did not succeed, along with the error to show the user.
+enum Failure {
+    /// The command itself was not one the program knows.
+    Invalid(RegistrarError),
+    /// The command ran but could not be carried out.
+    Failed(RegistrarError),
+}
+
+/// Runs a single command, either typed at the prompt or given on the command line. Commands and
+/// items can be given by their full names (like list students) or by their letters (like l s).
+fn run_command(store: &mut dyn RegistrarStore, input: Vec<&str>) -> std::result::Result<(), Failure> {
+    let input = expand_names(input);
+    // Check what command the user wants to run
+    match input[0] {
+        "a" => match insert_into_database(store, input) {
+            // Say what was being done instead of showing the database's own duplicate message
+            Err(RegistrarError::Duplicate(_)) => {
+                return Err(Failure::Failed(RegistrarError::Duplicate(String::from(
+                    "Unable to add item to database. Duplicate entry found.",
+                ))))
+            }
+            Err(error) => return Err(Failure::Failed(error)),
+            Ok(item) => println!("Successfully inserted '{item}' into database.\n"),
+        },
+        "d" => match delete_from_database(store, input) {
+            Err(error) => return Err(Failure::Failed(error)),
+            Ok(message) => println!("{message}\n"),
+        },
+        "e" => match edit_in_database(store, input) {
+            Err(RegistrarError::Duplicate(_)) => {
+                return Err(Failure::Failed(RegistrarError::Duplicate(String::from(
+                    "Unable to edit item. Duplicate entry found.",
+                ))))
+            }
+            Err(error) => return Err(Failure::Failed(error)),
+            Ok(item) => println!("Successfully updated '{item}' in database.\n"),
+        },
+        "l" => list_from_database(store, input).map_err(Failure::Failed)?,
+        "t" => transcript(store, input).map_err(Failure::Failed)?,
+        "migrate" => migrate(store, input).map_err(Failure::Failed)?,
+        _ => return Err(Failure::Invalid(RegistrarError::Validation(String::from(
+            "Invalid command. Valid commands are (a)dd, (d)elete, (e)dit, (l)ist, (t)ranscript, migrate, (q)uit.",
+        )))),
+    }
+    Ok(())
+}
+
+/// Swaps the full names of commands and items for the letters the command functions expect.
+fn expand_names(mut input: Vec<&str>) -> Vec<&str> {
+    input[0] = match input[0] {
+        "add" => "a",
+        "delete" => "d",
+        "edit" => "e",
+        "list" => "l",
+        "transcript" => "t",
+        command => command,
+    };
+    // Only these commands are followed by the kind of item they work on
+    if matches!(input[0], "a" | "d" | "e" | "l") && input.len() > 1 {
+        input[1] = match input[1] {
+            "course" | "courses" => "c",
+            "grade" | "grades" => "g",
+            "semester" | "semesters" => "m",
+            "term" | "terms" => "r",
+            "student" | "students" => "s",
+            "taken" | "taken-course" | "taken-courses" => "t",
+            item => item,
+        };
+    }
+    input
+}
+
+fn connect_mysql(settings: &Profile) -> Result<(Box<dyn RegistrarStore>, String)> {
+    // Get mysql connection info from the settings, prompting the user for anything missing
+    let hostname = match &settings.hostname {
+        Some(hostname) => hostname.clone(),
+        None => prompt_input("MySQL Hostname: ")?,
+    };
+    let port_num = match settings.port {
+        Some(port) => port.to_string(),
+        None => prompt_input("MySQL Port Num: ")?,
+    };
+    let username = match &settings.username {
+        Some(username) => username.clone(),
+        None => prompt_input("MySQL Username: ")?,
+    };
+    let password = match settings.read_password()? {
+        Some(password) => password,
+        None => {
+            require_terminal("MySQL Password: ")?;
+            prompt_password("MySQL Password: ")?
+        }
+    };
+    let database = match &settings.database {
+        Some(database) => database.clone(),
+        None => prompt_input("MySQL Database: ")?,
+    };
+
+    // Connect to mysql server using connection info. The options are set one by one instead of
+    // through a url so special characters in the password do not have to be escaped.
+    let port = match port_num.parse::<u16>() {
+        Ok(port) => port,
+        Err(_) => {
+            return Err(RegistrarError::Validation(String::from(
+                "Unable to connect. MySQL port must be a whole number from 0 to 65535.",
+            )))
+        }
+    };
+    let opts = OptsBuilder::new()
+        .ip_or_hostname(Some(hostname.as_str()))
+        .tcp_port(port)
+        .user(Some(username))
+        .pass(Some(password))
+        .db_name(Some(database.as_str()))
+        .ssl_opts(settings.ssl_opts());
+    let store = MySqlStore::connect(opts)?;
+
+    // Notify user that the connection has been made
+    let notice = format!("\nYou are now connected to '{hostname}' using '{database}' database.\n");
+    Ok((Box::new(store), notice))
+}
+
+/// Opens the store described by the given url and returns it along with the notice telling the
+/// user what they are connected to.
+fn open_store(url: &str, settings: &Profile) -> Result<(Box<dyn RegistrarStore>, String)> {
+    let store = store::open(url, settings)?;
+    let notice = if url.starts_with("mysql://") {
+        String::from("\nYou are now connected to the MySQL database.\n")
+    } else if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        String::from("\nYou are now connected to the PostgreSQL database.\n")
+    } else if url == "memory://" || url == "--memory" {
+        String::from("\nYou are now using an in-memory database. Nothing will be saved on exit.\n")
+    } else {
+        let path = url.strip_prefix("sqlite://").unwrap_or(url);
+        format!("\nYou are now using the SQLite database '{path}'.\n")
+    };
+    Ok((store, notice))
+}
+
+/// Prints the error that kept the program from starting and exits with a failing status.
+fn exit_with(error: RegistrarError) -> ! {
+    eprintln!("{error}");
+    process::exit(1);
+}
+
+/// Asks for a connection setting that was not given. The prompt goes to standard error so it never
+/// mixes with a command's output.
 fn prompt_input(prompt: &str) -> Result<String> {
-    print!("{prompt}");
-    stdout().flush()?;
+    require_terminal(prompt)?;
+    eprint!("{prompt}");
+    stderr().flush()?;
     let mut input = String::new();
     stdin().read_line(&mut input)?;
-    let len = input.trim_end_matches(&['\r', '\n']).len();
+    let len = input.trim_end_matches(['\r', '\n']).len();
     input.truncate(len);
     Ok(input)
 }
+
+/// Fails instead of prompting when standard input is not a terminal, since the answer would be read
+/// from whatever was piped in.
+fn require_terminal(prompt: &str) -> Result<()> {
+    if stdin().is_terminal() {
+        return Ok(());
+    }
+    Err(RegistrarError::Connection(format!(
+        "Unable to connect. {} was not given and there is no terminal to prompt for it. Give a database url or set it in the config file or environment.",
+        prompt.trim_end_matches(": "),
+    )))
+}
@@ -0,0 +1,583 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{Course, Grade, Semester, Student, TakenCourse, Term, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::migrations::{Direction, Migration};
+use crate::store::{still_referenced, DeletePolicy, Reference, Referenced, RegistrarStore};
+
+/// In-memory implementation of the registrar store. Nothing is saved once the program exits, which
+/// makes it useful for demos and for exercising the commands without a database server.
+#[derive(Default)]
+pub struct MemoryStore {
+    courses: Vec<Course>,
+    grades: Vec<Grade>,
+    semesters: Vec<Semester>,
+    terms: Vec<Term>,
+    students: Vec<Student>,
+    taken_courses: Vec<TakenCourse>,
+    schema_version: u32,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        // Start with the same terms the migration adds to a database
+        let terms = [("Winter", 1), ("Spring", 2), ("Summer", 3), ("Fall", 4)]
+            .into_iter()
+            .map(|(name, sequence)| Term {
+                name: String::from(name),
+                sequence,
+            })
+            .collect();
+        MemoryStore {
+            terms,
+            ..MemoryStore::default()
+        }
+    }
+
+    /// Checks the same references the foreign keys on TakenCourse do.
+    fn check_references(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        if self.search_student(taken_course.student_id)?.is_none() {
+            return Err(Reference::Student.missing());
+        } else if self.search_course(&taken_course.course_prefix, &taken_course.course_number)?.is_none() {
+            return Err(Reference::Course.missing());
+        } else if self.search_grade(&taken_course.grade_letter)?.is_none() {
+            return Err(Reference::Grade.missing());
+        } else if self.search_semester(&taken_course.semester_code)?.is_none() {
+            return Err(Reference::Semester.missing());
+        }
+        Ok(())
+    }
+}
+
+/// Builds the same error the other backends give when a primary key is already in use.
+fn duplicate_entry(key: String) -> RegistrarError {
+    RegistrarError::Duplicate(format!("Duplicate entry '{key}' for key 'PRIMARY'"))
+}
+
+/// Every column of TakenCourse is part of its primary key.
+fn same_taken_course(a: &TakenCourse, b: &TakenCourse) -> bool {
+    a.student_id == b.student_id
+        && a.course_prefix == b.course_prefix
+        && a.course_number == b.course_number
+        && a.grade_letter == b.grade_letter
+        && a.semester_code == b.semester_code
+}
+
+/// Checks if the taken course references the given item through one of its foreign keys.
+fn references(taken_course: &TakenCourse, item: Referenced) -> bool {
+    match item {
+        Referenced::Course(course) => {
+            taken_course.course_prefix == course.prefix && taken_course.course_number == course.number
+        }
+        Referenced::Grade(grade) => taken_course.grade_letter == grade.letter,
+        Referenced::Semester(semester) => taken_course.semester_code == semester.code,
+        Referenced::Student(student) => taken_course.student_id == student.id,
+    }
+}
+
+fn duplicate_taken_course(taken_course: &TakenCourse) -> RegistrarError {
+    duplicate_entry(format!(
+        "{}-{}-{}-{}-{}",
+        taken_course.student_id,
+        taken_course.course_prefix,
+        taken_course.course_number,
+        taken_course.grade_letter,
+        taken_course.semester_code,
+    ))
+}
+
+impl RegistrarStore for MemoryStore {
+    // Start migration functions
+    fn schema_version(&mut self) -> Result<u32> {
+        Ok(self.schema_version)
+    }
+
+    fn apply_migration(&mut self, migration: &Migration, direction: Direction) -> Result<()> {
+        // There are no tables to change, just keep track of the version
+        self.schema_version = match direction {
+            Direction::Up => migration.version,
+            Direction::Down => migration.version - 1,
+        };
+        Ok(())
+    }
+    // End migration functions
+
+    // Start insert functions
+    fn insert_course(&mut self, course: &Course) -> Result<()> {
+        if self.search_course(&course.prefix, &course.number)?.is_some() {
+            return Err(duplicate_entry(format!("{}-{}", course.prefix, course.number)));
+        }
+        self.courses.push(course.clone());
+        Ok(())
+    }
+
+    fn insert_grade(&mut self, grade: &Grade) -> Result<()> {
+        if self.search_grade(&grade.letter)?.is_some() {
+            return Err(duplicate_entry(grade.letter.clone()));
+        }
+        self.grades.push(grade.clone());
+        Ok(())
+    }
+
+    fn insert_semester(&mut self, semester: &Semester) -> Result<()> {
+        if self.search_semester(&semester.code)?.is_some() {
+            return Err(duplicate_entry(semester.code.clone()));
+        }
+        self.semesters.push(semester.clone());
+        Ok(())
+    }
+
+    fn insert_term(&mut self, term: &Term) -> Result<()> {
+        if self.search_term(&term.name)?.is_some() {
+            return Err(duplicate_entry(term.name.clone()));
+        }
+        self.terms.push(term.clone());
+        Ok(())
+    }
+
+    fn insert_student(&mut self, student: &Student) -> Result<u32> {
+        if self.search_student(student.id)?.is_some() {
+            return Err(duplicate_entry(student.id.to_string()));
+        }
+        let mut student = student.clone();
+        // Pick the next ID after the largest one in use like an auto increment column does
+        if student.id == 0 {
+            student.id = self.students.iter().map(|item| item.id).max().unwrap_or(0) + 1;
+        }
+        self.students.push(student.clone());
+        Ok(student.id)
+    }
+
+    fn insert_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.check_references(taken_course)?;
+        if self.taken_courses.iter().any(|item| same_taken_course(item, taken_course)) {
+            return Err(duplicate_taken_course(taken_course));
+        }
+        self.taken_courses.push(taken_course.clone());
+        Ok(())
+    }
+    // End insert functions
+
+    // Start search functions
+    fn search_course(&mut self, prefix: &str, number: &str) -> Result<Option<Course>> {
+        Ok(self
+            .courses
+            .iter()
+            .find(|course| course.prefix == prefix && course.number == number)
+            .cloned())
+    }
+
+    fn search_grade(&mut self, letter: &str) -> Result<Option<Grade>> {
+        Ok(self.grades.iter().find(|grade| grade.letter == letter).cloned())
+    }
+
+    fn search_semester(&mut self, code: &str) -> Result<Option<Semester>> {
+        Ok(self.semesters.iter().find(|semester| semester.code == code).cloned())
+    }
+
+    fn search_term(&mut self, name: &str) -> Result<Option<Term>> {
+        Ok(self.terms.iter().find(|term| term.name == name).cloned())
+    }
+
+    fn search_student(&mut self, id: u32) -> Result<Option<Student>> {
+        Ok(self.students.iter().find(|student| student.id == id).cloned())
+    }
+
+    fn search_students_by_name(&mut self, lname: &str, fname: &str) -> Result<Vec<Student>> {
+        Ok(self
+            .students
+            .iter()
+            .filter(|student| student.lname == lname && student.fname == fname)
+            .cloned()
+            .collect())
+    }
+    // End search functions
+
+    // Start update functions
+    fn update_course(&mut self, old: &Course, course: &Course) -> Result<()> {
+        let key_changed = old.prefix != course.prefix || old.number != course.number;
+        if key_changed && self.search_course(&course.prefix, &course.number)?.is_some() {
+            return Err(duplicate_entry(format!("{}-{}", course.prefix, course.number)));
+        }
+        for item in self.courses.iter_mut() {
+            if item.prefix == old.prefix && item.number == old.number {
+                *item = course.clone();
+            }
+        }
+        // Cascade the update the same way the foreign key on TakenCourse does
+        for item in self.taken_courses.iter_mut() {
+            if item.course_prefix == old.prefix && item.course_number == old.number {
+                item.course_prefix = course.prefix.clone();
+                item.course_number = course.number.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn update_grade(&mut self, old: &Grade, grade: &Grade) -> Result<()> {
+        if old.letter != grade.letter && self.search_grade(&grade.letter)?.is_some() {
+            return Err(duplicate_entry(grade.letter.clone()));
+        }
+        for item in self.grades.iter_mut() {
+            if item.letter == old.letter {
+                *item = grade.clone();
+            }
+        }
+        // Cascade the update the same way the foreign key on TakenCourse does
+        for item in self.taken_courses.iter_mut() {
+            if item.grade_letter == old.letter {
+                item.grade_letter = grade.letter.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn update_semester(&mut self, old: &Semester, semester: &Semester) -> Result<()> {
+        if old.code != semester.code && self.search_semester(&semester.code)?.is_some() {
+            return Err(duplicate_entry(semester.code.clone()));
+        }
+        for item in self.semesters.iter_mut() {
+            if item.code == old.code {
+                *item = semester.clone();
+            }
+        }
+        // Cascade the update the same way the foreign key on TakenCourse does
+        for item in self.taken_courses.iter_mut() {
+            if item.semester_code == old.code {
+                item.semester_code = semester.code.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn update_term(&mut self, old: &Term, term: &Term) -> Result<()> {
+        if old.name != term.name && self.search_term(&term.name)?.is_some() {
+            return Err(duplicate_entry(term.name.clone()));
+        }
+        for item in self.terms.iter_mut() {
+            if item.name == old.name {
+                *item = term.clone();
+            }
+        }
+        // Semesters name their term by description so they are renamed along with it
+        for item in self.semesters.iter_mut() {
+            if item.description == old.name {
+                item.description = term.name.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn update_student(&mut self, old: &Student, student: &Student) -> Result<()> {
+        if old.id != student.id && self.search_student(student.id)?.is_some() {
+            return Err(duplicate_entry(student.id.to_string()));
+        }
+        for item in self.students.iter_mut() {
+            if item.id == old.id {
+                *item = student.clone();
+            }
+        }
+        // Cascade the update the same way the foreign key on TakenCourse does
+        for item in self.taken_courses.iter_mut() {
+            if item.student_id == old.id {
+                item.student_id = student.id;
+            }
+        }
+        Ok(())
+    }
+
+    fn update_taken_course(&mut self, old: &TakenCourse, taken_course: &TakenCourse) -> Result<()> {
+        self.check_references(taken_course)?;
+        if !same_taken_course(old, taken_course)
+            && self.taken_courses.iter().any(|item| same_taken_course(item, taken_course))
+        {
+            return Err(duplicate_taken_course(taken_course));
+        }
+        for item in self.taken_courses.iter_mut() {
+            if same_taken_course(item, old) {
+                *item = taken_course.clone();
+            }
+        }
+        Ok(())
+    }
+    // End update functions
+
+    // Start delete functions
+    fn dependent_taken_courses(&mut self, item: Referenced) -> Result<Vec<TakenCourse>> {
+        Ok(self
+            .taken_courses
+            .iter()
+            .filter(|taken_course| references(taken_course, item))
+            .cloned()
+            .collect())
+    }
+
+    fn delete_item(&mut self, item: Referenced, policy: DeletePolicy) -> Result<usize> {
+        let dependents = self.dependent_taken_courses(item)?.len();
+        if dependents > 0 && policy == DeletePolicy::Refuse {
+            return Err(still_referenced(dependents));
+        }
+        self.taken_courses.retain(|taken_course| !references(taken_course, item));
+        match item {
+            Referenced::Course(course) => self
+                .courses
+                .retain(|item| !(item.prefix == course.prefix && item.number == course.number)),
+            Referenced::Grade(grade) => self.grades.retain(|item| item.letter != grade.letter),
+            Referenced::Semester(semester) => self.semesters.retain(|item| item.code != semester.code),
+            Referenced::Student(student) => self.students.retain(|item| item.id != student.id),
+        }
+        Ok(dependents)
+    }
+
+    fn delete_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.taken_courses.retain(|item| !same_taken_course(item, taken_course));
+        Ok(())
+    }
+
+    fn delete_term(&mut self, term: &Term) -> Result<()> {
+        self.terms.retain(|item| item.name != term.name);
+        Ok(())
+    }
+    // End delete functions
+
+    // Start list functions
+    fn list_courses(&mut self) -> Result<Vec<Course>> {
+        Ok(self.courses.clone())
+    }
+
+    fn list_grades(&mut self) -> Result<Vec<Grade>> {
+        Ok(self.grades.clone())
+    }
+
+    fn list_semesters(&mut self) -> Result<Vec<Semester>> {
+        let mut semesters = self.semesters.clone();
+        semesters.sort_by_key(|semester| semester.calendar_key(&self.terms));
+        Ok(semesters)
+    }
+
+    fn list_terms(&mut self) -> Result<Vec<Term>> {
+        let mut terms = self.terms.clone();
+        terms.sort_by(|a, b| (a.sequence, &a.name).cmp(&(b.sequence, &b.name)));
+        Ok(terms)
+    }
+
+    fn list_students(&mut self) -> Result<Vec<Student>> {
+        Ok(self.students.clone())
+    }
+
+    fn list_taken_courses(&mut self) -> Result<Vec<TakenCourse>> {
+        Ok(self.taken_courses.clone())
+    }
+    // End list functions
+
+    fn transcript(&mut self, student: &Student) -> Result<Vec<TranscriptRow>> {
+        let mut rows = Vec::new();
+        // Join each taken course with the rows it references
+        for taken_course in self.taken_courses.iter().filter(|item| item.student_id == student.id) {
+            let semester = self.semesters.iter().find(|item| item.code == taken_course.semester_code);
+            let course = self.courses.iter().find(|item| {
+                item.prefix == taken_course.course_prefix && item.number == taken_course.course_number
+            });
+            let grade = self.grades.iter().find(|item| item.letter == taken_course.grade_letter);
+            if let (Some(semester), Some(course), Some(grade)) = (semester, course, grade) {
+                rows.push(TranscriptRow {
+                    semester: semester.clone(),
+                    course: course.clone(),
+                    grade: grade.clone(),
+                });
+            }
+        }
+        rows.sort_by(|a, b| {
+            (a.semester.calendar_key(&self.terms), &a.course.prefix, &a.course.number)
+                .cmp(&(b.semester.calendar_key(&self.terms), &b.course.prefix, &b.course.number))
+        });
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbmgmt;
+    use rust_decimal::Decimal;
+
+    fn course(prefix: &str, number: &str) -> Course {
+        Course {
+            prefix: String::from(prefix),
+            number: String::from(number),
+            title: String::from("Data Structures"),
+            credits: 3,
+        }
+    }
+
+    fn grade(letter: &str) -> Grade {
+        Grade {
+            letter: String::from(letter),
+            value: Decimal::new(40, 1),
+            counts_toward_gpa: true,
+            earns_credit: true,
+            attempted: true,
+        }
+    }
+
+    fn semester(code: &str) -> Semester {
+        Semester {
+            code: String::from(code),
+            year: 2023,
+            description: String::from("Fall"),
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    fn student(id: u32) -> Student {
+        Student {
+            id,
+            lname: String::from("Duchars"),
+            fname: String::from("Grant"),
+            phone: String::from("555-0100"),
+        }
+    }
+
+    fn taken_course(student_id: u32, prefix: &str, number: &str) -> TakenCourse {
+        TakenCourse {
+            student_id,
+            course_prefix: String::from(prefix),
+            course_number: String::from(number),
+            grade_letter: String::from("A"),
+            semester_code: String::from("F23"),
+        }
+    }
+
+    /// A store with one of each item and a taken course that references all of them.
+    fn filled_store() -> MemoryStore {
+        let mut store = MemoryStore::new();
+        dbmgmt::add_course(&mut store, course("CS", "202")).unwrap();
+        dbmgmt::add_grade(&mut store, grade("A")).unwrap();
+        dbmgmt::add_semester(&mut store, semester("F23")).unwrap();
+        dbmgmt::add_student(&mut store, student(1)).unwrap();
+        dbmgmt::add_taken_course(&mut store, taken_course(1, "CS", "202")).unwrap();
+        store
+    }
+
+    #[test]
+    fn insert_update_and_delete() {
+        let mut store = filled_store();
+        assert_eq!(store.list_courses().unwrap(), vec![course("CS", "202")]);
+        let renamed = Course {
+            title: String::from("Algorithms"),
+            ..course("CS", "202")
+        };
+        dbmgmt::update_course(&mut store, &course("CS", "202"), renamed.clone()).unwrap();
+        assert_eq!(store.search_course("CS", "202").unwrap(), Some(renamed));
+        store.delete_taken_course(&taken_course(1, "CS", "202")).unwrap();
+        assert_eq!(store.delete_item(Referenced::Student(&student(1)), DeletePolicy::Refuse).unwrap(), 0);
+        assert_eq!(store.search_student(1).unwrap(), None);
+        assert!(store.list_taken_courses().unwrap().is_empty());
+    }
+
+    #[test]
+    fn student_id_of_zero_takes_the_next_one() {
+        let mut store = filled_store();
+        assert_eq!(dbmgmt::add_student(&mut store, student(0)).unwrap().id, 2);
+    }
+
+    #[test]
+    fn duplicates_are_refused() {
+        let mut store = filled_store();
+        let errors = [
+            dbmgmt::add_course(&mut store, course("CS", "202")).map(|_| ()),
+            dbmgmt::add_grade(&mut store, grade("A")).map(|_| ()),
+            dbmgmt::add_semester(&mut store, semester("F23")).map(|_| ()),
+            dbmgmt::add_student(&mut store, student(1)).map(|_| ()),
+            dbmgmt::add_taken_course(&mut store, taken_course(1, "CS", "202")).map(|_| ()),
+        ];
+        for error in errors {
+            assert!(matches!(error, Err(RegistrarError::Duplicate(_))));
+        }
+        // Changing a key to one already in use is refused too
+        dbmgmt::add_course(&mut store, course("CS", "203")).unwrap();
+        let result = dbmgmt::update_course(&mut store, &course("CS", "203"), course("CS", "202"));
+        assert!(matches!(result, Err(RegistrarError::Duplicate(_))));
+    }
+
+    #[test]
+    fn missing_references_are_refused() {
+        let mut store = filled_store();
+        let missing = [
+            (taken_course(2, "CS", "202"), "student"),
+            (taken_course(1, "CS", "999"), "course"),
+            (
+                TakenCourse {
+                    grade_letter: String::from("Z"),
+                    ..taken_course(1, "CS", "202")
+                },
+                "grade",
+            ),
+            (
+                TakenCourse {
+                    semester_code: String::from("S99"),
+                    ..taken_course(1, "CS", "202")
+                },
+                "semester",
+            ),
+        ];
+        for (item, name) in missing {
+            match dbmgmt::add_taken_course(&mut store, item) {
+                Err(RegistrarError::Constraint(message)) => {
+                    assert!(message.ends_with(&format!("Given {name} does not exist.")), "{message}")
+                }
+                result => panic!("expected a missing {name}, got {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn referenced_items_are_kept_unless_cascaded() {
+        let mut store = filled_store();
+        let result = store.delete_item(Referenced::Course(&course("CS", "202")), DeletePolicy::Refuse);
+        assert!(matches!(result, Err(RegistrarError::Constraint(_))));
+        assert_eq!(store.list_taken_courses().unwrap().len(), 1);
+        assert_eq!(store.delete_item(Referenced::Course(&course("CS", "202")), DeletePolicy::Cascade).unwrap(), 1);
+        assert!(store.list_taken_courses().unwrap().is_empty());
+        assert!(store.list_courses().unwrap().is_empty());
+    }
+
+    #[test]
+    fn taken_courses_follow_a_changed_key() {
+        let mut store = filled_store();
+        dbmgmt::update_course(&mut store, &course("CS", "202"), course("CSCI", "2020")).unwrap();
+        dbmgmt::update_student(&mut store, &student(1), student(7)).unwrap();
+        dbmgmt::update_semester(&mut store, &semester("F23"), semester("FA23")).unwrap();
+        dbmgmt::update_grade(&mut store, &grade("A"), grade("A+")).unwrap();
+        let expected = TakenCourse {
+            grade_letter: String::from("A+"),
+            semester_code: String::from("FA23"),
+            ..taken_course(7, "CSCI", "2020")
+        };
+        assert_eq!(store.list_taken_courses().unwrap(), vec![expected]);
+    }
+
+    #[test]
+    fn semester_years_are_bounded() {
+        let mut store = MemoryStore::new();
+        for year in [0, 10000, 40000] {
+            let item = Semester { year, ..semester("F23") };
+            assert!(matches!(dbmgmt::add_semester(&mut store, item), Err(RegistrarError::Validation(_))));
+        }
+        dbmgmt::add_semester(&mut store, Semester { year: 9999, ..semester("F23") }).unwrap();
+        let result = dbmgmt::update_semester(&mut store, &semester("F23"), Semester { year: 0, ..semester("F23") });
+        assert!(matches!(result, Err(RegistrarError::Validation(_))));
+    }
+
+    #[test]
+    fn semesters_follow_a_renamed_term() {
+        let mut store = filled_store();
+        let fall = store.search_term("Fall").unwrap().unwrap();
+        let autumn = Term {
+            name: String::from("Autumn"),
+            ..fall.clone()
+        };
+        dbmgmt::update_term(&mut store, &fall, autumn).unwrap();
+        assert_eq!(store.search_semester("F23").unwrap().unwrap().description, "Autumn");
+    }
+}
@@ -0,0 +1,605 @@
+// Author: Grant Duchars
+use crate::error::Result;
+use crate::store::RegistrarStore;
+
+/// The SQL statements for one migration step written for each database dialect.
+pub struct Sql {
+    pub mysql: &'static [&'static str],
+    pub postgres: &'static [&'static str],
+    pub sqlite: &'static [&'static str],
+}
+
+/// A single versioned change to the database schema along with the statements to undo it.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: Sql,
+    pub down: Sql,
+}
+
+/// Which way a migration is being applied.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Every migration in the order they are applied. New schema changes are added to the end of this
+/// list and must never change a migration that has already been released.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create initial tables",
+        // Tables are only created if missing so databases made before migrations existed are adopted
+        up: Sql {
+            mysql: &[
+                r"CREATE TABLE IF NOT EXISTS Course (
+                Prefix nvarchar(5) not null,
+                Number nvarchar(5) not null,
+                Title nvarchar(32) not null,
+                Credits nvarchar(5) not null,
+                PRIMARY KEY (Prefix, Number))",
+                r"CREATE TABLE IF NOT EXISTS Grade (
+                Letter nvarchar(5) not null,
+                Value nvarchar(5) not null,
+                PRIMARY KEY (Letter))",
+                r"CREATE TABLE IF NOT EXISTS Semester (
+                Code nvarchar(4) not null,
+                Year nvarchar(4) not null,
+                Description varchar(6) not null,
+                PRIMARY KEY (Code))",
+                r"CREATE TABLE IF NOT EXISTS Student (
+                LName nvarchar(15) not null,
+                FName nvarchar(15) not null,
+                Phone nvarchar(20) not null,
+                PRIMARY KEY (LName, FName))",
+                r"CREATE TABLE IF NOT EXISTS TakenCourse (
+                StudentLName nvarchar(32) not null,
+                StudentFName nvarchar(32) not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode))",
+            ],
+            postgres: &[
+                r"CREATE TABLE IF NOT EXISTS Course (
+                Prefix varchar(5) not null,
+                Number varchar(5) not null,
+                Title varchar(32) not null,
+                Credits varchar(5) not null,
+                PRIMARY KEY (Prefix, Number))",
+                r"CREATE TABLE IF NOT EXISTS Grade (
+                Letter varchar(5) not null,
+                Value varchar(5) not null,
+                PRIMARY KEY (Letter))",
+                r"CREATE TABLE IF NOT EXISTS Semester (
+                Code varchar(4) not null,
+                Year varchar(4) not null,
+                Description varchar(6) not null,
+                PRIMARY KEY (Code))",
+                r"CREATE TABLE IF NOT EXISTS Student (
+                LName varchar(15) not null,
+                FName varchar(15) not null,
+                Phone varchar(20) not null,
+                PRIMARY KEY (LName, FName))",
+                r"CREATE TABLE IF NOT EXISTS TakenCourse (
+                StudentLName varchar(32) not null,
+                StudentFName varchar(32) not null,
+                CoursePrefix varchar(5) not null,
+                CourseNumber varchar(5) not null,
+                GradeLetter varchar(5) not null,
+                SemesterCode varchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode))",
+            ],
+            sqlite: &[
+                r"CREATE TABLE IF NOT EXISTS Course (
+                Prefix nvarchar(5) not null,
+                Number nvarchar(5) not null,
+                Title nvarchar(32) not null,
+                Credits nvarchar(5) not null,
+                PRIMARY KEY (Prefix, Number))",
+                r"CREATE TABLE IF NOT EXISTS Grade (
+                Letter nvarchar(5) not null,
+                Value nvarchar(5) not null,
+                PRIMARY KEY (Letter))",
+                r"CREATE TABLE IF NOT EXISTS Semester (
+                Code nvarchar(4) not null,
+                Year nvarchar(4) not null,
+                Description varchar(6) not null,
+                PRIMARY KEY (Code))",
+                r"CREATE TABLE IF NOT EXISTS Student (
+                LName nvarchar(15) not null,
+                FName nvarchar(15) not null,
+                Phone nvarchar(20) not null,
+                PRIMARY KEY (LName, FName))",
+                r"CREATE TABLE IF NOT EXISTS TakenCourse (
+                StudentLName nvarchar(32) not null,
+                StudentFName nvarchar(32) not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode))",
+            ],
+        },
+        down: Sql {
+            mysql: &[
+                "DROP TABLE TakenCourse",
+                "DROP TABLE Student",
+                "DROP TABLE Semester",
+                "DROP TABLE Grade",
+                "DROP TABLE Course",
+            ],
+            postgres: &[
+                "DROP TABLE TakenCourse",
+                "DROP TABLE Student",
+                "DROP TABLE Semester",
+                "DROP TABLE Grade",
+                "DROP TABLE Course",
+            ],
+            sqlite: &[
+                "DROP TABLE TakenCourse",
+                "DROP TABLE Student",
+                "DROP TABLE Semester",
+                "DROP TABLE Grade",
+                "DROP TABLE Course",
+            ],
+        },
+    },
+    Migration {
+        version: 2,
+        description: "Add foreign keys to TakenCourse",
+        up: Sql {
+            mysql: &[r"ALTER TABLE TakenCourse
+            ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Course FOREIGN KEY (CoursePrefix, CourseNumber)
+                REFERENCES Course (Prefix, Number) ON DELETE RESTRICT ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Grade FOREIGN KEY (GradeLetter)
+                REFERENCES Grade (Letter) ON DELETE RESTRICT ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Semester FOREIGN KEY (SemesterCode)
+                REFERENCES Semester (Code) ON DELETE RESTRICT ON UPDATE CASCADE"],
+            postgres: &[r"ALTER TABLE TakenCourse
+            ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Course FOREIGN KEY (CoursePrefix, CourseNumber)
+                REFERENCES Course (Prefix, Number) ON DELETE RESTRICT ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Grade FOREIGN KEY (GradeLetter)
+                REFERENCES Grade (Letter) ON DELETE RESTRICT ON UPDATE CASCADE,
+            ADD CONSTRAINT FK_TakenCourse_Semester FOREIGN KEY (SemesterCode)
+                REFERENCES Semester (Code) ON DELETE RESTRICT ON UPDATE CASCADE"],
+            // SQLite cannot add constraints to a table so it has to be rebuilt
+            sqlite: &[
+                r"CREATE TABLE TakenCourseNew (
+                StudentLName nvarchar(32) not null,
+                StudentFName nvarchar(32) not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode),
+                CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                    REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Course FOREIGN KEY (CoursePrefix, CourseNumber)
+                    REFERENCES Course (Prefix, Number) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Grade FOREIGN KEY (GradeLetter)
+                    REFERENCES Grade (Letter) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Semester FOREIGN KEY (SemesterCode)
+                    REFERENCES Semester (Code) ON DELETE RESTRICT ON UPDATE CASCADE)",
+                "INSERT INTO TakenCourseNew SELECT * FROM TakenCourse",
+                "DROP TABLE TakenCourse",
+                "ALTER TABLE TakenCourseNew RENAME TO TakenCourse",
+            ],
+        },
+        down: Sql {
+            mysql: &[
+                r"ALTER TABLE TakenCourse
+                DROP FOREIGN KEY FK_TakenCourse_Student,
+                DROP FOREIGN KEY FK_TakenCourse_Course,
+                DROP FOREIGN KEY FK_TakenCourse_Grade,
+                DROP FOREIGN KEY FK_TakenCourse_Semester",
+                // MySQL made an index for each key that was not already covered by the primary key
+                r"ALTER TABLE TakenCourse
+                DROP INDEX FK_TakenCourse_Course,
+                DROP INDEX FK_TakenCourse_Grade,
+                DROP INDEX FK_TakenCourse_Semester",
+            ],
+            postgres: &[r"ALTER TABLE TakenCourse
+            DROP CONSTRAINT FK_TakenCourse_Student,
+            DROP CONSTRAINT FK_TakenCourse_Course,
+            DROP CONSTRAINT FK_TakenCourse_Grade,
+            DROP CONSTRAINT FK_TakenCourse_Semester"],
+            sqlite: &[
+                r"CREATE TABLE TakenCourseNew (
+                StudentLName nvarchar(32) not null,
+                StudentFName nvarchar(32) not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode))",
+                "INSERT INTO TakenCourseNew SELECT * FROM TakenCourse",
+                "DROP TABLE TakenCourse",
+                "ALTER TABLE TakenCourseNew RENAME TO TakenCourse",
+            ],
+        },
+    },
+    Migration {
+        version: 3,
+        description: "Store credits, grade values and years as numbers",
+        up: Sql {
+            mysql: &[
+                "ALTER TABLE Course MODIFY Credits tinyint unsigned not null",
+                "ALTER TABLE Grade MODIFY Value decimal(3,2) not null",
+                "ALTER TABLE Semester MODIFY Year smallint unsigned not null",
+            ],
+            postgres: &[
+                "ALTER TABLE Course ALTER COLUMN Credits TYPE smallint USING Credits::smallint",
+                "ALTER TABLE Grade ALTER COLUMN Value TYPE numeric(3,2) USING Value::numeric",
+                "ALTER TABLE Semester ALTER COLUMN Year TYPE smallint USING Year::smallint",
+            ],
+            // SQLite cannot change the type of a column so the tables have to be rebuilt
+            sqlite: &[
+                r"CREATE TABLE CourseNew (
+                Prefix nvarchar(5) not null,
+                Number nvarchar(5) not null,
+                Title nvarchar(32) not null,
+                Credits tinyint not null,
+                PRIMARY KEY (Prefix, Number))",
+                "INSERT INTO CourseNew SELECT Prefix, Number, Title, CAST(Credits AS INTEGER) FROM Course",
+                "DROP TABLE Course",
+                "ALTER TABLE CourseNew RENAME TO Course",
+                r"CREATE TABLE GradeNew (
+                Letter nvarchar(5) not null,
+                Value decimal(3,2) not null,
+                PRIMARY KEY (Letter))",
+                "INSERT INTO GradeNew SELECT Letter, CAST(Value AS NUMERIC) FROM Grade",
+                "DROP TABLE Grade",
+                "ALTER TABLE GradeNew RENAME TO Grade",
+                r"CREATE TABLE SemesterNew (
+                Code nvarchar(4) not null,
+                Year smallint not null,
+                Description varchar(6) not null,
+                PRIMARY KEY (Code))",
+                "INSERT INTO SemesterNew SELECT Code, CAST(Year AS INTEGER), Description FROM Semester",
+                "DROP TABLE Semester",
+                "ALTER TABLE SemesterNew RENAME TO Semester",
+            ],
+        },
+        down: Sql {
+            mysql: &[
+                "ALTER TABLE Course MODIFY Credits nvarchar(5) not null",
+                "ALTER TABLE Grade MODIFY Value nvarchar(5) not null",
+                "ALTER TABLE Semester MODIFY Year nvarchar(4) not null",
+            ],
+            postgres: &[
+                "ALTER TABLE Course ALTER COLUMN Credits TYPE varchar(5) USING Credits::varchar",
+                "ALTER TABLE Grade ALTER COLUMN Value TYPE varchar(5) USING Value::varchar",
+                "ALTER TABLE Semester ALTER COLUMN Year TYPE varchar(4) USING Year::varchar",
+            ],
+            sqlite: &[
+                r"CREATE TABLE CourseNew (
+                Prefix nvarchar(5) not null,
+                Number nvarchar(5) not null,
+                Title nvarchar(32) not null,
+                Credits nvarchar(5) not null,
+                PRIMARY KEY (Prefix, Number))",
+                "INSERT INTO CourseNew SELECT Prefix, Number, Title, CAST(Credits AS TEXT) FROM Course",
+                "DROP TABLE Course",
+                "ALTER TABLE CourseNew RENAME TO Course",
+                r"CREATE TABLE GradeNew (
+                Letter nvarchar(5) not null,
+                Value nvarchar(5) not null,
+                PRIMARY KEY (Letter))",
+                "INSERT INTO GradeNew SELECT Letter, CAST(Value AS TEXT) FROM Grade",
+                "DROP TABLE Grade",
+                "ALTER TABLE GradeNew RENAME TO Grade",
+                r"CREATE TABLE SemesterNew (
+                Code nvarchar(4) not null,
+                Year nvarchar(4) not null,
+                Description varchar(6) not null,
+                PRIMARY KEY (Code))",
+                "INSERT INTO SemesterNew SELECT Code, CAST(Year AS TEXT), Description FROM Semester",
+                "DROP TABLE Semester",
+                "ALTER TABLE SemesterNew RENAME TO Semester",
+            ],
+        },
+    },
+    Migration {
+        version: 4,
+        description: "Key students by ID instead of name",
+        up: Sql {
+            mysql: &[
+                "ALTER TABLE TakenCourse DROP FOREIGN KEY FK_TakenCourse_Student",
+                // Existing students are numbered when the auto increment column is added
+                r"ALTER TABLE Student
+                DROP PRIMARY KEY,
+                ADD COLUMN ID int unsigned not null AUTO_INCREMENT PRIMARY KEY FIRST,
+                ADD INDEX IX_Student_Name (LName, FName)",
+                "ALTER TABLE TakenCourse ADD COLUMN StudentID int unsigned not null FIRST",
+                r"UPDATE TakenCourse JOIN Student
+                ON TakenCourse.StudentLName = Student.LName AND TakenCourse.StudentFName = Student.FName
+                SET TakenCourse.StudentID = Student.ID",
+                r"ALTER TABLE TakenCourse
+                DROP PRIMARY KEY,
+                DROP COLUMN StudentLName,
+                DROP COLUMN StudentFName,
+                ADD PRIMARY KEY (StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode),
+                ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentID)
+                    REFERENCES Student (ID) ON DELETE CASCADE ON UPDATE CASCADE",
+            ],
+            postgres: &[
+                "ALTER TABLE TakenCourse DROP CONSTRAINT FK_TakenCourse_Student",
+                // Existing students are numbered when the serial column is added
+                r"ALTER TABLE Student
+                DROP CONSTRAINT student_pkey,
+                ADD COLUMN ID bigserial PRIMARY KEY",
+                "CREATE INDEX IX_Student_Name ON Student (LName, FName)",
+                "ALTER TABLE TakenCourse ADD COLUMN StudentID bigint",
+                r"UPDATE TakenCourse SET StudentID = Student.ID FROM Student
+                WHERE TakenCourse.StudentLName = Student.LName AND TakenCourse.StudentFName = Student.FName",
+                r"ALTER TABLE TakenCourse
+                DROP CONSTRAINT takencourse_pkey,
+                DROP COLUMN StudentLName,
+                DROP COLUMN StudentFName,
+                ALTER COLUMN StudentID SET NOT NULL,
+                ADD PRIMARY KEY (StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode),
+                ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentID)
+                    REFERENCES Student (ID) ON DELETE CASCADE ON UPDATE CASCADE",
+            ],
+            // An integer primary key is SQLite's rowid, so it picks the next ID when none is given
+            sqlite: &[
+                r"CREATE TABLE StudentNew (
+                ID integer not null,
+                LName nvarchar(15) not null,
+                FName nvarchar(15) not null,
+                Phone nvarchar(20) not null,
+                PRIMARY KEY (ID))",
+                "INSERT INTO StudentNew (LName, FName, Phone) SELECT LName, FName, Phone FROM Student",
+                r"CREATE TABLE TakenCourseNew (
+                StudentID integer not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode),
+                CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentID)
+                    REFERENCES Student (ID) ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Course FOREIGN KEY (CoursePrefix, CourseNumber)
+                    REFERENCES Course (Prefix, Number) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Grade FOREIGN KEY (GradeLetter)
+                    REFERENCES Grade (Letter) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Semester FOREIGN KEY (SemesterCode)
+                    REFERENCES Semester (Code) ON DELETE RESTRICT ON UPDATE CASCADE)",
+                r"INSERT INTO TakenCourseNew
+                SELECT StudentNew.ID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse JOIN StudentNew
+                ON TakenCourse.StudentLName = StudentNew.LName AND TakenCourse.StudentFName = StudentNew.FName",
+                "DROP TABLE TakenCourse",
+                "DROP TABLE Student",
+                "ALTER TABLE StudentNew RENAME TO Student",
+                "ALTER TABLE TakenCourseNew RENAME TO TakenCourse",
+                "CREATE INDEX IX_Student_Name ON Student (LName, FName)",
+            ],
+        },
+        // Going back fails if two students share a name since names become the key again
+        down: Sql {
+            mysql: &[
+                "ALTER TABLE TakenCourse DROP FOREIGN KEY FK_TakenCourse_Student",
+                r"ALTER TABLE TakenCourse
+                ADD COLUMN StudentLName nvarchar(32) not null FIRST,
+                ADD COLUMN StudentFName nvarchar(32) not null AFTER StudentLName",
+                r"UPDATE TakenCourse JOIN Student ON TakenCourse.StudentID = Student.ID
+                SET TakenCourse.StudentLName = Student.LName, TakenCourse.StudentFName = Student.FName",
+                r"ALTER TABLE TakenCourse
+                DROP PRIMARY KEY,
+                DROP COLUMN StudentID,
+                ADD PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode)",
+                r"ALTER TABLE Student
+                DROP PRIMARY KEY,
+                DROP INDEX IX_Student_Name,
+                DROP COLUMN ID,
+                ADD PRIMARY KEY (LName, FName)",
+                r"ALTER TABLE TakenCourse
+                ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                    REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE",
+            ],
+            postgres: &[
+                r"ALTER TABLE TakenCourse
+                DROP CONSTRAINT FK_TakenCourse_Student,
+                ADD COLUMN StudentLName varchar(32),
+                ADD COLUMN StudentFName varchar(32)",
+                r"UPDATE TakenCourse SET StudentLName = Student.LName, StudentFName = Student.FName
+                FROM Student WHERE TakenCourse.StudentID = Student.ID",
+                r"ALTER TABLE TakenCourse
+                DROP CONSTRAINT takencourse_pkey,
+                DROP COLUMN StudentID,
+                ALTER COLUMN StudentLName SET NOT NULL,
+                ALTER COLUMN StudentFName SET NOT NULL,
+                ADD PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode)",
+                "DROP INDEX IX_Student_Name",
+                r"ALTER TABLE Student
+                DROP COLUMN ID,
+                ADD PRIMARY KEY (LName, FName)",
+                r"ALTER TABLE TakenCourse
+                ADD CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                    REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE",
+            ],
+            sqlite: &[
+                r"CREATE TABLE StudentNew (
+                LName nvarchar(15) not null,
+                FName nvarchar(15) not null,
+                Phone nvarchar(20) not null,
+                PRIMARY KEY (LName, FName))",
+                "INSERT INTO StudentNew SELECT LName, FName, Phone FROM Student",
+                r"CREATE TABLE TakenCourseNew (
+                StudentLName nvarchar(32) not null,
+                StudentFName nvarchar(32) not null,
+                CoursePrefix nvarchar(5) not null,
+                CourseNumber nvarchar(5) not null,
+                GradeLetter nvarchar(5) not null,
+                SemesterCode nvarchar(5) not null,
+                PRIMARY KEY (StudentLName, StudentFName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode),
+                CONSTRAINT FK_TakenCourse_Student FOREIGN KEY (StudentLName, StudentFName)
+                    REFERENCES Student (LName, FName) ON DELETE CASCADE ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Course FOREIGN KEY (CoursePrefix, CourseNumber)
+                    REFERENCES Course (Prefix, Number) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Grade FOREIGN KEY (GradeLetter)
+                    REFERENCES Grade (Letter) ON DELETE RESTRICT ON UPDATE CASCADE,
+                CONSTRAINT FK_TakenCourse_Semester FOREIGN KEY (SemesterCode)
+                    REFERENCES Semester (Code) ON DELETE RESTRICT ON UPDATE CASCADE)",
+                r"INSERT INTO TakenCourseNew
+                SELECT Student.LName, Student.FName, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse JOIN Student ON TakenCourse.StudentID = Student.ID",
+                "DROP TABLE TakenCourse",
+                "DROP TABLE Student",
+                "ALTER TABLE StudentNew RENAME TO Student",
+                "ALTER TABLE TakenCourseNew RENAME TO TakenCourse",
+            ],
+        },
+    },
+    Migration {
+        version: 5,
+        description: "Add terms and semester dates",
+        // Semesters are put in order within a year by the sequence of the term their description names
+        up: Sql {
+            mysql: &[
+                r"CREATE TABLE Term (
+                Name nvarchar(6) not null,
+                Sequence tinyint unsigned not null,
+                PRIMARY KEY (Name))",
+                "INSERT INTO Term VALUES ('Winter', 1), ('Spring', 2), ('Summer', 3), ('Fall', 4)",
+                r"ALTER TABLE Semester
+                ADD COLUMN StartDate date,
+                ADD COLUMN EndDate date",
+            ],
+            postgres: &[
+                r"CREATE TABLE Term (
+                Name varchar(6) not null,
+                Sequence smallint not null,
+                PRIMARY KEY (Name))",
+                "INSERT INTO Term VALUES ('Winter', 1), ('Spring', 2), ('Summer', 3), ('Fall', 4)",
+                r"ALTER TABLE Semester
+                ADD COLUMN StartDate date,
+                ADD COLUMN EndDate date",
+            ],
+            // SQLite has no date type, dates are kept as YYYY-MM-DD text which sorts in date order
+            sqlite: &[
+                r"CREATE TABLE Term (
+                Name nvarchar(6) not null,
+                Sequence integer not null,
+                PRIMARY KEY (Name))",
+                "INSERT INTO Term VALUES ('Winter', 1), ('Spring', 2), ('Summer', 3), ('Fall', 4)",
+                "ALTER TABLE Semester ADD COLUMN StartDate text",
+                "ALTER TABLE Semester ADD COLUMN EndDate text",
+            ],
+        },
+        down: Sql {
+            mysql: &[
+                r"ALTER TABLE Semester
+                DROP COLUMN StartDate,
+                DROP COLUMN EndDate",
+                "DROP TABLE Term",
+            ],
+            postgres: &[
+                r"ALTER TABLE Semester
+                DROP COLUMN StartDate,
+                DROP COLUMN EndDate",
+                "DROP TABLE Term",
+            ],
+            sqlite: &[
+                "ALTER TABLE Semester DROP COLUMN StartDate",
+                "ALTER TABLE Semester DROP COLUMN EndDate",
+                "DROP TABLE Term",
+            ],
+        },
+    },
+    Migration {
+        version: 6,
+        description: "Add GPA, credit and attempted flags to grades",
+        // Existing grades keep counting toward everything except that a zero point grade earns no credit
+        up: Sql {
+            mysql: &[
+                r"ALTER TABLE Grade
+                ADD COLUMN CountsTowardGpa boolean not null DEFAULT true,
+                ADD COLUMN EarnsCredit boolean not null DEFAULT true,
+                ADD COLUMN Attempted boolean not null DEFAULT true",
+                "UPDATE Grade SET EarnsCredit = false WHERE Value = 0",
+            ],
+            postgres: &[
+                r"ALTER TABLE Grade
+                ADD COLUMN CountsTowardGpa boolean not null DEFAULT true,
+                ADD COLUMN EarnsCredit boolean not null DEFAULT true,
+                ADD COLUMN Attempted boolean not null DEFAULT true",
+                "UPDATE Grade SET EarnsCredit = false WHERE Value = 0",
+            ],
+            sqlite: &[
+                "ALTER TABLE Grade ADD COLUMN CountsTowardGpa boolean not null DEFAULT 1",
+                "ALTER TABLE Grade ADD COLUMN EarnsCredit boolean not null DEFAULT 1",
+                "ALTER TABLE Grade ADD COLUMN Attempted boolean not null DEFAULT 1",
+                "UPDATE Grade SET EarnsCredit = 0 WHERE Value = 0",
+            ],
+        },
+        down: Sql {
+            mysql: &[
+                r"ALTER TABLE Grade
+                DROP COLUMN CountsTowardGpa,
+                DROP COLUMN EarnsCredit,
+                DROP COLUMN Attempted",
+            ],
+            postgres: &[
+                r"ALTER TABLE Grade
+                DROP COLUMN CountsTowardGpa,
+                DROP COLUMN EarnsCredit,
+                DROP COLUMN Attempted",
+            ],
+            sqlite: &[
+                "ALTER TABLE Grade DROP COLUMN CountsTowardGpa",
+                "ALTER TABLE Grade DROP COLUMN EarnsCredit",
+                "ALTER TABLE Grade DROP COLUMN Attempted",
+            ],
+        },
+    },
+];
+
+/// Returns the version of the newest migration.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Applies every pending migration up to and including the target version (or all of them if no
+/// target is given). Returns the migrations that were applied.
+pub fn migrate_up(store: &mut dyn RegistrarStore, target: Option<u32>) -> Result<Vec<&'static Migration>> {
+    let current = store.schema_version()?;
+    let target = target.unwrap_or_else(latest_version);
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter() {
+        if migration.version > current && migration.version <= target {
+            store.apply_migration(migration, Direction::Up)?;
+            applied.push(migration);
+        }
+    }
+    Ok(applied)
+}
+
+/// Reverts applied migrations newest first until the schema is at the target version (or just the
+/// newest one if no target is given). Returns the migrations that were reverted.
+pub fn migrate_down(store: &mut dyn RegistrarStore, target: Option<u32>) -> Result<Vec<&'static Migration>> {
+    let current = store.schema_version()?;
+    let target = target.unwrap_or_else(|| current.saturating_sub(1));
+    let mut reverted = Vec::new();
+    for migration in MIGRATIONS.iter().rev() {
+        if migration.version <= current && migration.version > target {
+            store.apply_migration(migration, Direction::Down)?;
+            reverted.push(migration);
+        }
+    }
+    Ok(reverted)
+}
+
+/// Returns every migration along with whether it has been applied to the database.
+pub fn status(store: &mut dyn RegistrarStore) -> Result<Vec<(&'static Migration, bool)>> {
+    let current = store.schema_version()?;
+    Ok(MIGRATIONS.iter().map(|migration| (migration, migration.version <= current)).collect())
+}
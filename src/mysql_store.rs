@@ -0,0 +1,790 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{Course, Grade, Semester, Student, TakenCourse, Term, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::migrations::{Direction, Migration};
+use crate::store::{
+    still_referenced, DeletePolicy, Reference, Referenced, RegistrarStore, SEMESTER_ORDER, SEMESTER_TERM_JOIN,
+};
+use mysql::prelude::*;
+use mysql::*;
+use std::thread;
+use std::time::Duration;
+
+/// How many times a read is tried again after the connection to the server is lost.
+const RETRIES: u32 = 3;
+/// How long to wait before the first retry. Each retry after it waits twice as long as the last.
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// MySQL implementation of the registrar store. Keeps the pool its connection came from so a
+/// connection dropped by a server restart or idle timeout can be replaced.
+pub struct MySqlStore {
+    pool: Pool,
+    conn: PooledConn,
+}
+
+impl MySqlStore {
+    /// Connects to the MySQL server described by the given options.
+    pub fn connect(opts: impl Into<Opts>) -> Result<MySqlStore> {
+        let connection_error =
+            |error: mysql::Error| RegistrarError::Connection(format!("Unable to connect to the MySQL server. {error}"));
+        let pool = Pool::new(opts).map_err(connection_error)?;
+        let conn = pool.get_conn().map_err(connection_error)?;
+        Ok(MySqlStore { pool, conn })
+    }
+
+    /// Runs a query that only reads from the database, so it is safe to run again. If the connection
+    /// was lost a new one is taken from the pool and the query is tried again, up to RETRIES times.
+    fn read<T>(&mut self, mut query: impl FnMut(&mut PooledConn) -> Result<T>) -> Result<T> {
+        let mut delay = RETRY_DELAY;
+        for _ in 0..RETRIES {
+            match query(&mut self.conn) {
+                Err(RegistrarError::Connection(_)) => {
+                    // Give the server a moment to come back before trying again
+                    thread::sleep(delay);
+                    delay *= 2;
+                    // The pool checks the connection it hands out, so a failure here just means the
+                    // server is still down and the next try will fail the same way
+                    if let Ok(conn) = self.pool.get_conn() {
+                        self.conn = conn;
+                    }
+                }
+                result => return result,
+            }
+        }
+        query(&mut self.conn).map_err(|error| match error {
+            RegistrarError::Connection(message) => RegistrarError::Connection(format!(
+                "Lost the connection to the MySQL server and could not reconnect after {RETRIES} tries. {message}"
+            )),
+            error => error,
+        })
+    }
+
+    /// Runs a change to the database in a transaction. The connection is checked first so one that was
+    /// dropped while idle is replaced before anything is sent. The server rolls back a change whose
+    /// connection is lost before the commit, but once the commit is sent the change is never run again
+    /// since the server may have applied it before the connection was lost.
+    fn write<T>(&mut self, change: impl FnOnce(&mut Transaction) -> Result<T>) -> Result<T> {
+        self.read(|conn| Ok(conn.query_drop("SELECT 1")?)).map_err(not_applied)?;
+        // Start a transaction
+        let mut transaction = self
+            .conn
+            .start_transaction(TxOpts::default())
+            .map_err(|error| not_applied(RegistrarError::from(error)))?;
+        let value = change(&mut transaction).map_err(not_applied)?;
+        // Commit the transaction
+        transaction.commit().map_err(|error| maybe_applied(RegistrarError::from(error)))?;
+        Ok(value)
+    }
+}
+
+/// Says nothing was changed when the connection was lost before a change was committed.
+fn not_applied(error: RegistrarError) -> RegistrarError {
+    match error {
+        RegistrarError::Connection(message) => RegistrarError::Connection(format!(
+            "Lost the connection to the MySQL server before the change was saved, so nothing was changed. {message}"
+        )),
+        error => error,
+    }
+}
+
+/// Says the change may have been applied when the connection was lost after it was sent to be saved.
+fn maybe_applied(error: RegistrarError) -> RegistrarError {
+    match error {
+        RegistrarError::Connection(message) => RegistrarError::Connection(format!(
+            "Lost the connection to the MySQL server while saving the change, so it may or may not have been applied. Check the item before trying again. {message}"
+        )),
+        error => error,
+    }
+}
+
+/// Says the schema was left partly migrated when a migration failed after some of its schema changes
+/// were saved. MySQL cannot undo those, so they have to be finished or undone by hand.
+fn partly_migrated(error: RegistrarError, migration: &Migration, done: usize, total: usize) -> RegistrarError {
+    error.during(&format!(
+        "Migration {} failed after {done} of its {total} schema changes were saved, and its version was not recorded. The schema may be partly migrated, so finish or undo the remaining changes by hand before running it again",
+        migration.version,
+    ))
+}
+
+/// Turns MySQL's "cannot add or update a child row" error into a message saying which referenced
+/// row does not exist.
+fn foreign_key_error(error: mysql::Error) -> RegistrarError {
+    match error {
+        mysql::Error::MySqlError(ref my_sql_error) if my_sql_error.code == 1452 => {
+            match Reference::from_constraint(&my_sql_error.message) {
+                Some(reference) => reference.missing(),
+                None => RegistrarError::from(error),
+            }
+        }
+        _ => RegistrarError::from(error),
+    }
+}
+
+/// Returns the statement that deletes the given item, the condition that picks out the taken courses
+/// that reference it, and the item's key which both of them take as parameters.
+fn item_key(item: Referenced) -> (&'static str, &'static str, Params) {
+    match item {
+        Referenced::Course(course) => (
+            "DELETE FROM Course WHERE Course.Prefix = ? AND Course.Number = ?",
+            "TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?",
+            Params::from((&course.prefix, &course.number)),
+        ),
+        Referenced::Grade(grade) => (
+            "DELETE FROM Grade WHERE Grade.Letter = ?",
+            "TakenCourse.GradeLetter = ?",
+            Params::from((&grade.letter,)),
+        ),
+        Referenced::Semester(semester) => (
+            "DELETE FROM Semester WHERE Semester.Code = ?",
+            "TakenCourse.SemesterCode = ?",
+            Params::from((&semester.code,)),
+        ),
+        Referenced::Student(student) => (
+            "DELETE FROM Student WHERE Student.ID = ?",
+            "TakenCourse.StudentID = ?",
+            Params::from((student.id,)),
+        ),
+    }
+}
+
+impl RegistrarStore for MySqlStore {
+    // Start migration functions
+    fn schema_version(&mut self) -> Result<u32> {
+        self.read(|conn| {
+            // Create the table that tracks applied migrations if it does not exist
+            conn.query_drop(
+                r"CREATE TABLE IF NOT EXISTS SchemaVersion (
+                Version int unsigned not null,
+                Description nvarchar(64) not null,
+                PRIMARY KEY (Version))",
+            )?;
+            let version = conn.query_first("SELECT COALESCE(MAX(Version), 0) FROM SchemaVersion")?;
+            Ok(version.unwrap_or(0))
+        })
+    }
+
+    fn apply_migration(&mut self, migration: &Migration, direction: Direction) -> Result<()> {
+        self.read(|conn| Ok(conn.query_drop("SELECT 1")?)).map_err(not_applied)?;
+        let statements = match direction {
+            Direction::Up => migration.up.mysql,
+            Direction::Down => migration.down.mysql,
+        };
+        // MySQL commits schema changes right away, so these cannot share a transaction and a failure
+        // partway through leaves the earlier ones applied
+        for (done, statement) in statements.iter().enumerate() {
+            if let Err(error) = self.conn.query_drop(statement) {
+                return Err(match done {
+                    0 => maybe_applied(RegistrarError::from(error)),
+                    _ => partly_migrated(RegistrarError::from(error), migration, done, statements.len()),
+                });
+            }
+        }
+        // Record the new schema version
+        let recorded = match direction {
+            Direction::Up => self.conn.exec_drop(
+                "INSERT INTO SchemaVersion (Version, Description) VALUES (?, ?)",
+                (migration.version, migration.description),
+            ),
+            Direction::Down => self.conn.exec_drop(
+                "DELETE FROM SchemaVersion WHERE Version = ?",
+                (migration.version,),
+            ),
+        };
+        recorded.map_err(|error| match statements.len() {
+            0 => maybe_applied(RegistrarError::from(error)),
+            total => partly_migrated(RegistrarError::from(error), migration, total, total),
+        })
+    }
+    // End migration functions
+
+    // Start insert functions
+    fn insert_course(&mut self, course: &Course) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO Course (Prefix, Number, Title, Credits)
+                VALUES (?, ?, ?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &course.prefix,
+                    &course.number,
+                    &course.title,
+                    &course.credits,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn insert_grade(&mut self, grade: &Grade) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO Grade (Letter, Value, CountsTowardGpa, EarnsCredit, Attempted)
+                VALUES (?, ?, ?, ?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &grade.letter,
+                    &grade.value,
+                    grade.counts_toward_gpa,
+                    grade.earns_credit,
+                    grade.attempted,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn insert_semester(&mut self, semester: &Semester) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO Semester (Code, Year, Description, StartDate, EndDate)
+                VALUES (?, ?, ?, ?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &semester.code,
+                    &semester.year,
+                    &semester.description,
+                    semester.start_date,
+                    semester.end_date,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn insert_term(&mut self, term: &Term) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO Term (Name, Sequence)
+                VALUES (?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(stmt, (&term.name, term.sequence))?;
+            Ok(())
+        })
+    }
+
+    fn insert_student(&mut self, student: &Student) -> Result<u32> {
+        self.write(|transaction| {
+            // Inserting a null ID has the auto increment column pick the next one
+            let id = if student.id == 0 { None } else { Some(student.id) };
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO Student (ID, LName, FName, Phone)
+                VALUES (?, ?, ?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(stmt, (id, &student.lname, &student.fname, &student.phone))?;
+            let id = u32::try_from(transaction.last_insert_id().unwrap_or(0))
+                .map_err(|_| RegistrarError::Internal(String::from("The new student's ID is out of range.")))?;
+            Ok(id)
+        })
+    }
+
+    fn insert_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"INSERT INTO TakenCourse (
+                    StudentID,
+                    CoursePrefix,
+                    CourseNumber,
+                    GradeLetter,
+                    SemesterCode)
+                VALUES (?, ?, ?, ?, ?)",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    taken_course.student_id,
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                ),
+            )
+            .map_err(foreign_key_error)?;
+            Ok(())
+        })
+    }
+    // End insert functions
+
+    // Start search functions
+    fn search_course(&mut self, prefix: &str, number: &str) -> Result<Option<Course>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep("SELECT * FROM Course WHERE Course.Prefix = ? AND Course.Number = ?")?;
+            // Execute query in prepared statement with given variables and keep the first result
+            let query = conn.exec_first(stmt, (prefix, number))?;
+            Ok(query.map(|(prefix, number, title, credits)| Course {
+                prefix,
+                number,
+                title,
+                credits,
+            }))
+        })
+    }
+
+    fn search_grade(&mut self, letter: &str) -> Result<Option<Grade>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep(
+                r"SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade
+                WHERE Grade.Letter = ?",
+            )?;
+            // Execute query in prepared statement with given variables and keep the first result
+            let query = conn.exec_first(stmt, (letter,))?;
+            Ok(query.map(|(letter, value, counts_toward_gpa, earns_credit, attempted)| Grade {
+                letter,
+                value,
+                counts_toward_gpa,
+                earns_credit,
+                attempted,
+            }))
+        })
+    }
+
+    fn search_semester(&mut self, code: &str) -> Result<Option<Semester>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep(
+                r"SELECT Code, Year, Description, StartDate, EndDate FROM Semester
+                WHERE Semester.Code = ?",
+            )?;
+            // Execute query in prepared statement with given variables and keep the first result
+            let query = conn.exec_first(stmt, (code,))?;
+            Ok(query.map(|(code, year, description, start_date, end_date)| Semester {
+                code,
+                year,
+                description,
+                start_date,
+                end_date,
+            }))
+        })
+    }
+
+    fn search_term(&mut self, name: &str) -> Result<Option<Term>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep("SELECT Name, Sequence FROM Term WHERE Term.Name = ?")?;
+            // Execute query in prepared statement with given variables and keep the first result
+            let query = conn.exec_first(stmt, (name,))?;
+            Ok(query.map(|(name, sequence)| Term { name, sequence }))
+        })
+    }
+
+    fn search_student(&mut self, id: u32) -> Result<Option<Student>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep("SELECT ID, LName, FName, Phone FROM Student WHERE Student.ID = ?")?;
+            // Execute query in prepared statement with given variables and keep the first result
+            let query = conn.exec_first(stmt, (id,))?;
+            Ok(query.map(|(id, lname, fname, phone)| Student {
+                id,
+                lname,
+                fname,
+                phone,
+            }))
+        })
+    }
+
+    fn search_students_by_name(&mut self, lname: &str, fname: &str) -> Result<Vec<Student>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep(
+                r"SELECT ID, LName, FName, Phone FROM Student
+                WHERE Student.LName = ? AND Student.FName = ?
+                ORDER BY Student.ID",
+            )?;
+            // Execute query in prepared statement with given variables
+            Ok(conn.exec_map(stmt, (lname, fname), |(id, lname, fname, phone)| Student {
+                id,
+                lname,
+                fname,
+                phone,
+            })?)
+        })
+    }
+    // End search functions
+
+    // Start update functions
+    fn update_course(&mut self, old: &Course, course: &Course) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE Course SET Prefix = ?, Number = ?, Title = ?, Credits = ?
+                WHERE Course.Prefix = ? AND Course.Number = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &course.prefix,
+                    &course.number,
+                    &course.title,
+                    &course.credits,
+                    &old.prefix,
+                    &old.number,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn update_grade(&mut self, old: &Grade, grade: &Grade) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE Grade SET Letter = ?, Value = ?, CountsTowardGpa = ?, EarnsCredit = ?, Attempted = ?
+                WHERE Grade.Letter = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &grade.letter,
+                    &grade.value,
+                    grade.counts_toward_gpa,
+                    grade.earns_credit,
+                    grade.attempted,
+                    &old.letter,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn update_semester(&mut self, old: &Semester, semester: &Semester) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE Semester SET Code = ?, Year = ?, Description = ?, StartDate = ?, EndDate = ?
+                WHERE Semester.Code = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    &semester.code,
+                    &semester.year,
+                    &semester.description,
+                    semester.start_date,
+                    semester.end_date,
+                    &old.code,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn update_term(&mut self, old: &Term, term: &Term) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE Term SET Name = ?, Sequence = ?
+                WHERE Term.Name = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(stmt, (&term.name, term.sequence, &old.name))?;
+            // Semesters name their term by description, so rename them in the same transaction
+            let stmt = transaction.prep("UPDATE Semester SET Description = ? WHERE Semester.Description = ?")?;
+            transaction.exec_drop(stmt, (&term.name, &old.name))?;
+            Ok(())
+        })
+    }
+
+    fn update_student(&mut self, old: &Student, student: &Student) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE Student SET ID = ?, LName = ?, FName = ?, Phone = ?
+                WHERE Student.ID = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (student.id, &student.lname, &student.fname, &student.phone, old.id),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn update_taken_course(&mut self, old: &TakenCourse, taken_course: &TakenCourse) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"UPDATE TakenCourse
+                SET StudentID = ?, CoursePrefix = ?, CourseNumber = ?, GradeLetter = ?, SemesterCode = ?
+                WHERE TakenCourse.StudentID = ? AND TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?
+                AND TakenCourse.GradeLetter = ? AND TakenCourse.SemesterCode = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    taken_course.student_id,
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                    old.student_id,
+                    &old.course_prefix,
+                    &old.course_number,
+                    &old.grade_letter,
+                    &old.semester_code,
+                ),
+            )
+            .map_err(foreign_key_error)?;
+            Ok(())
+        })
+    }
+    // End update functions
+
+    // Start delete functions
+    fn dependent_taken_courses(&mut self, item: Referenced) -> Result<Vec<TakenCourse>> {
+        self.read(|conn| {
+            let (_, condition, key) = item_key(item);
+            // Make a prepared statement
+            let stmt = conn.prep(format!(
+                r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse
+                WHERE {condition}"
+            ))?;
+            // Execute query in prepared statement with given variables
+            Ok(conn.exec_map(
+                stmt,
+                key,
+                |(student_id, course_prefix, course_number, grade_letter, semester_code)| TakenCourse {
+                    student_id,
+                    course_prefix,
+                    course_number,
+                    grade_letter,
+                    semester_code,
+                },
+            )?)
+        })
+    }
+
+    fn delete_item(&mut self, item: Referenced, policy: DeletePolicy) -> Result<usize> {
+        self.write(|transaction| {
+            let (delete, condition, key) = item_key(item);
+            let dependents: usize = transaction
+                .exec_first(format!("SELECT COUNT(*) FROM TakenCourse WHERE {condition}"), key.clone())?
+                .unwrap_or(0);
+            if dependents > 0 {
+                if policy == DeletePolicy::Refuse {
+                    return Err(still_referenced(dependents));
+                }
+                transaction.exec_drop(format!("DELETE FROM TakenCourse WHERE {condition}"), key.clone())?;
+            }
+            transaction.exec_drop(delete, key)?;
+            Ok(dependents)
+        })
+    }
+
+    fn delete_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep(
+                r"DELETE FROM TakenCourse
+                WHERE TakenCourse.StudentID = ? AND TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?
+                AND TakenCourse.GradeLetter = ? AND TakenCourse.SemesterCode = ?",
+            )?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(
+                stmt,
+                (
+                    taken_course.student_id,
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                ),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn delete_term(&mut self, term: &Term) -> Result<()> {
+        self.write(|transaction| {
+            // Make a prepared statement
+            let stmt = transaction.prep("DELETE FROM Term WHERE Term.Name = ?")?;
+            // Execute query in prepared statement with given variables
+            transaction.exec_drop(stmt, (&term.name,))?;
+            Ok(())
+        })
+    }
+    // End delete functions
+
+    // Start list functions
+    fn list_courses(&mut self) -> Result<Vec<Course>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                r"SELECT * FROM Course",
+                |(prefix, number, title, credits)| Course {
+                    prefix,
+                    number,
+                    title,
+                    credits,
+                },
+            )?)
+        })
+    }
+
+    fn list_grades(&mut self) -> Result<Vec<Grade>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                r"SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade",
+                |(letter, value, counts_toward_gpa, earns_credit, attempted)| Grade {
+                    letter,
+                    value,
+                    counts_toward_gpa,
+                    earns_credit,
+                    attempted,
+                },
+            )?)
+        })
+    }
+
+    fn list_semesters(&mut self) -> Result<Vec<Semester>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                format!(
+                    r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate
+                    FROM Semester {SEMESTER_TERM_JOIN}
+                    ORDER BY {SEMESTER_ORDER}"
+                ),
+                |(code, year, description, start_date, end_date)| Semester {
+                    code,
+                    year,
+                    description,
+                    start_date,
+                    end_date,
+                },
+            )?)
+        })
+    }
+
+    fn list_terms(&mut self) -> Result<Vec<Term>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                r"SELECT Name, Sequence FROM Term ORDER BY Term.Sequence, Term.Name",
+                |(name, sequence)| Term { name, sequence },
+            )?)
+        })
+    }
+
+    fn list_students(&mut self) -> Result<Vec<Student>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                r"SELECT ID, LName, FName, Phone FROM Student",
+                |(id, lname, fname, phone)| Student {
+                    id,
+                    lname,
+                    fname,
+                    phone,
+                },
+            )?)
+        })
+    }
+
+    fn list_taken_courses(&mut self) -> Result<Vec<TakenCourse>> {
+        self.read(|conn| {
+            // Select all rows from table
+            Ok(conn.query_map(
+                r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse",
+                |(
+                    student_id,
+                    course_prefix,
+                    course_number,
+                    grade_letter,
+                    semester_code,
+                )| {
+                    TakenCourse {
+                        student_id,
+                        course_prefix,
+                        course_number,
+                        grade_letter,
+                        semester_code,
+                    }
+                },
+            )?)
+        })
+    }
+    // End list functions
+
+    fn transcript(&mut self, student: &Student) -> Result<Vec<TranscriptRow>> {
+        self.read(|conn| {
+            // Make a prepared statement
+            let stmt = conn.prep(format!(
+                r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate,
+                Course.Prefix, Course.Number, Course.Title, Course.Credits,
+                Grade.Letter, Grade.Value, Grade.CountsTowardGpa, Grade.EarnsCredit, Grade.Attempted
+                FROM TakenCourse
+                JOIN Semester ON TakenCourse.SemesterCode = Semester.Code
+                JOIN Course ON TakenCourse.CoursePrefix = Course.Prefix AND TakenCourse.CourseNumber = Course.Number
+                JOIN Grade ON TakenCourse.GradeLetter = Grade.Letter
+                {SEMESTER_TERM_JOIN}
+                WHERE TakenCourse.StudentID = ?
+                ORDER BY {SEMESTER_ORDER}, Course.Prefix, Course.Number"
+            ))?;
+            // Execute query in prepared statement with given variables. There are more columns than a
+            // tuple can hold so they are taken out of each row one at a time
+            let rows: Vec<Row> = conn.exec(stmt, (student.id,))?;
+            rows.into_iter()
+                .map(|mut row| {
+                    Ok(TranscriptRow {
+                        semester: Semester {
+                            code: take_column(&mut row, 0)?,
+                            year: take_column(&mut row, 1)?,
+                            description: take_column(&mut row, 2)?,
+                            start_date: take_column(&mut row, 3)?,
+                            end_date: take_column(&mut row, 4)?,
+                        },
+                        course: Course {
+                            prefix: take_column(&mut row, 5)?,
+                            number: take_column(&mut row, 6)?,
+                            title: take_column(&mut row, 7)?,
+                            credits: take_column(&mut row, 8)?,
+                        },
+                        grade: Grade {
+                            letter: take_column(&mut row, 9)?,
+                            value: take_column(&mut row, 10)?,
+                            counts_toward_gpa: take_column(&mut row, 11)?,
+                            earns_credit: take_column(&mut row, 12)?,
+                            attempted: take_column(&mut row, 13)?,
+                        },
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// Takes the value of one column out of the row, converting it to the wanted type.
+fn take_column<T: FromValue>(row: &mut Row, index: usize) -> Result<T> {
+    match row.take_opt(index) {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(FromValueError(value))) => Err(RegistrarError::from(mysql::Error::FromValueError(value))),
+        None => Err(RegistrarError::from(mysql::Error::FromRowError(row.clone()))),
+    }
+}
@@ -0,0 +1,280 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{Course, Grade, Semester, Student, TakenCourse, Term};
+use crate::error::{RegistrarError, Result};
+use serde::Serialize;
+
+/// The formats lists of items can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// An ASCII table like the ones shown at the prompt.
+    Table,
+    /// Comma separated values with a header row.
+    Csv,
+    /// Tab separated values with a header row.
+    Tsv,
+    /// A JSON array of objects.
+    Json,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl Format {
+    /// Names of the formats that from_name knows.
+    pub const NAMES: [&'static str; 5] = ["table", "csv", "tsv", "json", "jsonl"];
+
+    /// Returns the format with the given name, which is one of NAMES.
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "table" => Some(Format::Table),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "json" => Some(Format::Json),
+            "jsonl" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// One column of a list. The width is only used by tables.
+pub struct Column {
+    pub name: &'static str,
+    pub width: usize,
+    /// Line the values up on the right side of the column instead of the left.
+    pub right: bool,
+}
+
+impl Column {
+    pub const fn left(name: &'static str, width: usize) -> Column {
+        Column { name, width, right: false }
+    }
+
+    pub const fn right(name: &'static str, width: usize) -> Column {
+        Column { name, width, right: true }
+    }
+}
+
+/// An item that can be written as one row of a list. Tables, CSV and TSV use the columns and
+/// fields, and JSON uses the item's own fields.
+pub trait Record: Serialize {
+    const COLUMNS: &'static [Column];
+    /// The item's value for each of the columns, in the same order.
+    fn fields(&self) -> Vec<String>;
+}
+
+/// Writes the items in the given format. Every line ends with a newline.
+pub fn render<T: Record>(items: &[T], format: Format) -> Result<String> {
+    match format {
+        Format::Table => Ok(table(items)),
+        Format::Csv => Ok(separated(items, ',', csv_escape)),
+        Format::Tsv => Ok(separated(items, '\t', tsv_escape)),
+        Format::Json => Ok(format!("{}\n", to_json(&items, true)?)),
+        Format::JsonLines => {
+            let mut output = String::new();
+            for item in items.iter() {
+                output.push_str(&format!("{}\n", to_json(item, false)?));
+            }
+            Ok(output)
+        }
+    }
+}
+
+fn table<T: Record>(items: &[T]) -> String {
+    let mut border = String::new();
+    let mut header = String::new();
+    for column in T::COLUMNS.iter() {
+        border.push_str(&format!("+{}", "-".repeat(column.width + 2)));
+        header.push_str(&format!("| {:<width$} ", column.name, width = column.width));
+    }
+    border.push_str("+\n");
+    header.push_str("|\n");
+    // Print out header for table
+    let mut output = format!("{border}{header}{border}");
+    // Iterate through items and print out styled table
+    for item in items.iter() {
+        for (column, field) in T::COLUMNS.iter().zip(item.fields()) {
+            if column.right {
+                output.push_str(&format!("| {:>width$} ", field, width = column.width));
+            } else {
+                output.push_str(&format!("| {:<width$} ", field, width = column.width));
+            }
+        }
+        output.push_str("|\n");
+    }
+    output.push_str(&border);
+    output
+}
+
+fn separated<T: Record>(items: &[T], separator: char, escape: fn(&str) -> String) -> String {
+    let line = |fields: Vec<String>| {
+        let fields: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+        format!("{}\n", fields.join(&separator.to_string()))
+    };
+    let mut output = line(T::COLUMNS.iter().map(|column| column.name.to_string()).collect());
+    for item in items.iter() {
+        output.push_str(&line(item.fields()));
+    }
+    output
+}
+
+/// Quotes a CSV field if it has a comma, quote or line break in it, doubling any quotes.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes tabs, line breaks and backslashes in a TSV field as \t, \n, \r and \\ so every row stays
+/// on one line.
+fn tsv_escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T, pretty: bool) -> Result<String> {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    json.map_err(|error| RegistrarError::Internal(format!("Unable to write JSON. {error}")))
+}
+
+fn yes_no(set: bool) -> String {
+    String::from(if set { "yes" } else { "no" })
+}
+
+// Start record functions
+impl Record for Course {
+    const COLUMNS: &'static [Column] = &[
+        Column::left("Prefix", 6),
+        Column::right("Number", 6),
+        Column::left("Title", 32),
+        Column::right("Credits", 7),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.prefix.clone(), self.number.clone(), self.title.clone(), self.credits.to_string()]
+    }
+}
+
+impl Record for Grade {
+    const COLUMNS: &'static [Column] = &[
+        Column::left("Type", 4),
+        Column::right("Value", 5),
+        Column::left("GPA", 3),
+        Column::left("Credit", 6),
+        Column::left("Attempted", 9),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.letter.clone(),
+            self.value.to_string(),
+            yes_no(self.counts_toward_gpa),
+            yes_no(self.earns_credit),
+            yes_no(self.attempted),
+        ]
+    }
+}
+
+impl Record for Semester {
+    const COLUMNS: &'static [Column] = &[
+        Column::left("Code", 4),
+        Column::right("Year", 4),
+        Column::left("Desc", 6),
+        Column::left("Start", 10),
+        Column::left("End", 10),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.year.to_string(),
+            self.description.clone(),
+            self.start_date.map(|date| date.to_string()).unwrap_or_default(),
+            self.end_date.map(|date| date.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+impl Record for Term {
+    const COLUMNS: &'static [Column] = &[Column::left("Name", 6), Column::right("Sequence", 8)];
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.name.clone(), self.sequence.to_string()]
+    }
+}
+
+impl Record for Student {
+    const COLUMNS: &'static [Column] = &[
+        Column::right("ID", 10),
+        Column::left("Last Name", 15),
+        Column::left("First Name", 15),
+        Column::right("Phone Number", 20),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![self.id.to_string(), self.lname.clone(), self.fname.clone(), self.phone.clone()]
+    }
+}
+
+impl Record for TakenCourse {
+    const COLUMNS: &'static [Column] = &[
+        Column::right("Student ID", 10),
+        Column::left("Prefix", 6),
+        Column::right("Number", 6),
+        Column::left("Type", 4),
+        Column::left("Code", 4),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.student_id.to_string(),
+            self.course_prefix.clone(),
+            self.course_number.clone(),
+            self.grade_letter.clone(),
+            self.semester_code.clone(),
+        ]
+    }
+}
+// End record functions
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_escape("Databases"), "Databases");
+        assert_eq!(csv_escape("Intro, Part 1"), "\"Intro, Part 1\"");
+        assert_eq!(csv_escape("The \"Best\" Course"), "\"The \"\"Best\"\" Course\"");
+        assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_escape("carriage\rreturn"), "\"carriage\rreturn\"");
+    }
+
+    #[test]
+    fn tsv_fields_stay_on_one_line() {
+        assert_eq!(tsv_escape("Databases"), "Databases");
+        assert_eq!(tsv_escape("a\tb"), "a\\tb");
+        assert_eq!(tsv_escape("two\nlines\r"), "two\\nlines\\r");
+        // Backslashes are escaped first so they cannot be mistaken for an escape
+        assert_eq!(tsv_escape("C:\\new"), "C:\\\\new");
+    }
+
+    #[test]
+    fn csv_has_a_header_row() {
+        let course = Course {
+            prefix: String::from("CSC"),
+            number: String::from("150"),
+            title: String::from("Intro, Part 1"),
+            credits: 4,
+        };
+        let output = render(&[course], Format::Csv).unwrap();
+        assert_eq!(output, "Prefix,Number,Title,Credits\nCSC,150,\"Intro, Part 1\",4\n");
+    }
+}
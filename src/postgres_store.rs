@@ -0,0 +1,693 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{Course, Grade, Semester, Student, TakenCourse, Term, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::migrations::{Direction, Migration};
+use crate::store::{
+    still_referenced, DeletePolicy, Reference, Referenced, RegistrarStore, SEMESTER_ORDER, SEMESTER_TERM_JOIN,
+};
+use postgres::error::SqlState;
+use postgres::types::{FromSql, ToSql};
+use postgres::{Client, NoTls, Row};
+use std::fmt::Display;
+
+/// PostgreSQL implementation of the registrar store.
+pub struct PostgresStore {
+    client: Client,
+}
+
+impl PostgresStore {
+    /// Connects to the PostgreSQL server described by the given postgres:// url.
+    pub fn connect(url: &str) -> Result<PostgresStore> {
+        let client = Client::connect(url, NoTls).map_err(|error| {
+            // The reason the connection failed is kept in the error's source
+            let reason = std::error::Error::source(&error).map(|source| format!(": {source}")).unwrap_or_default();
+            RegistrarError::Connection(format!("Unable to connect to the PostgreSQL server. {error}{reason}"))
+        })?;
+        Ok(PostgresStore { client })
+    }
+}
+
+/// Sorts a PostgreSQL error by what went wrong, using its SQLSTATE when the server sent one.
+fn postgres_error(error: postgres::Error) -> RegistrarError {
+    // Say which referenced row is missing when a foreign key is violated
+    if error.code() == Some(&SqlState::FOREIGN_KEY_VIOLATION) {
+        let reference = error
+            .as_db_error()
+            .and_then(|db_error| db_error.constraint())
+            .and_then(Reference::from_constraint);
+        if let Some(reference) = reference {
+            return reference.missing();
+        }
+    }
+    match error.code() {
+        Some(code) if *code == SqlState::UNIQUE_VIOLATION => RegistrarError::Duplicate(error.to_string()),
+        // Class 23 is integrity constraint violations
+        Some(code) if code.code().starts_with("23") => RegistrarError::Constraint(error.to_string()),
+        // Class 08 is connection exceptions
+        Some(code) if code.code().starts_with("08") => RegistrarError::Connection(error.to_string()),
+        None if error.is_closed() => RegistrarError::Connection(error.to_string()),
+        _ => RegistrarError::Internal(error.to_string()),
+    }
+}
+
+impl RegistrarStore for PostgresStore {
+    // Start migration functions
+    fn schema_version(&mut self) -> Result<u32> {
+        // Create the table that tracks applied migrations if it does not exist
+        self.client
+            .batch_execute(
+                r"CREATE TABLE IF NOT EXISTS SchemaVersion (
+                Version int not null,
+                Description varchar(64) not null,
+                PRIMARY KEY (Version))",
+            )
+            .map_err(postgres_error)?;
+        let row = self
+            .client
+            .query_one("SELECT COALESCE(MAX(Version), 0) FROM SchemaVersion", &[])
+            .map_err(postgres_error)?;
+        unsigned::<i32, u32>(&row, 0)
+    }
+
+    fn apply_migration(&mut self, migration: &Migration, direction: Direction) -> Result<()> {
+        let statements = match direction {
+            Direction::Up => migration.up.postgres,
+            Direction::Down => migration.down.postgres,
+        };
+        // Start a transaction so a failed migration leaves the schema untouched
+        let mut transaction = self.client.transaction().map_err(postgres_error)?;
+        for statement in statements.iter() {
+            transaction.batch_execute(statement).map_err(postgres_error)?;
+        }
+        // Record the new schema version
+        let version = i32::try_from(migration.version)
+            .map_err(|_| RegistrarError::Internal(format!("Migration {} is out of range.", migration.version)))?;
+        match direction {
+            Direction::Up => transaction.execute(
+                "INSERT INTO SchemaVersion (Version, Description) VALUES ($1, $2)",
+                &[&version, &migration.description],
+            ),
+            Direction::Down => transaction.execute(
+                "DELETE FROM SchemaVersion WHERE Version = $1",
+                &[&version],
+            ),
+        }
+        .map_err(postgres_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(postgres_error)
+    }
+    // End migration functions
+
+    // Start insert functions
+    fn insert_course(&mut self, course: &Course) -> Result<()> {
+        self.client
+            .execute(
+                r"INSERT INTO Course (Prefix, Number, Title, Credits)
+                VALUES ($1, $2, $3, $4)",
+                &[&course.prefix, &course.number, &course.title, &i16::from(course.credits)],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn insert_grade(&mut self, grade: &Grade) -> Result<()> {
+        self.client
+            .execute(
+                r"INSERT INTO Grade (Letter, Value, CountsTowardGpa, EarnsCredit, Attempted)
+                VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &grade.letter,
+                    &grade.value,
+                    &grade.counts_toward_gpa,
+                    &grade.earns_credit,
+                    &grade.attempted,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn insert_semester(&mut self, semester: &Semester) -> Result<()> {
+        let year = smallint(semester.year)?;
+        self.client
+            .execute(
+                r"INSERT INTO Semester (Code, Year, Description, StartDate, EndDate)
+                VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &semester.code,
+                    &year,
+                    &semester.description,
+                    &semester.start_date,
+                    &semester.end_date,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn insert_term(&mut self, term: &Term) -> Result<()> {
+        self.client
+            .execute(
+                r"INSERT INTO Term (Name, Sequence)
+                VALUES ($1, $2)",
+                &[&term.name, &i16::from(term.sequence)],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn insert_student(&mut self, student: &Student) -> Result<u32> {
+        if student.id == 0 {
+            // Let the ID column's sequence pick the next one
+            let row = self
+                .client
+                .query_one(
+                    r"INSERT INTO Student (LName, FName, Phone)
+                    VALUES ($1, $2, $3)
+                    RETURNING ID",
+                    &[&student.lname, &student.fname, &student.phone],
+                )
+                .map_err(postgres_error)?;
+            return unsigned::<i64, u32>(&row, 0);
+        }
+        // Start a transaction
+        let mut transaction = self.client.transaction().map_err(postgres_error)?;
+        transaction
+            .execute(
+                r"INSERT INTO Student (ID, LName, FName, Phone)
+                VALUES ($1, $2, $3, $4)",
+                &[&i64::from(student.id), &student.lname, &student.fname, &student.phone],
+            )
+            .map_err(postgres_error)?;
+        // Move the sequence past the given ID so it is not handed out again
+        transaction
+            .execute(
+                "SELECT setval(pg_get_serial_sequence('student', 'id'), MAX(ID)) FROM Student",
+                &[],
+            )
+            .map_err(postgres_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(postgres_error)?;
+        Ok(student.id)
+    }
+
+    fn insert_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.client
+            .execute(
+                r"INSERT INTO TakenCourse (
+                    StudentID,
+                    CoursePrefix,
+                    CourseNumber,
+                    GradeLetter,
+                    SemesterCode)
+                VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &i64::from(taken_course.student_id),
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+    // End insert functions
+
+    // Start search functions
+    fn search_course(&mut self, prefix: &str, number: &str) -> Result<Option<Course>> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT * FROM Course WHERE Course.Prefix = $1 AND Course.Number = $2",
+                &[&prefix, &number],
+            )
+            .map_err(postgres_error)?;
+        row.map(|row| course_from_row(&row)).transpose()
+    }
+
+    fn search_grade(&mut self, letter: &str) -> Result<Option<Grade>> {
+        let row = self
+            .client
+            .query_opt(
+                r"SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade
+                WHERE Grade.Letter = $1",
+                &[&letter],
+            )
+            .map_err(postgres_error)?;
+        row.map(|row| grade_from_row(&row)).transpose()
+    }
+
+    fn search_semester(&mut self, code: &str) -> Result<Option<Semester>> {
+        let row = self
+            .client
+            .query_opt(
+                r"SELECT Code, Year, Description, StartDate, EndDate FROM Semester
+                WHERE Semester.Code = $1",
+                &[&code],
+            )
+            .map_err(postgres_error)?;
+        row.map(|row| semester_from_row(&row)).transpose()
+    }
+
+    fn search_term(&mut self, name: &str) -> Result<Option<Term>> {
+        let row = self
+            .client
+            .query_opt("SELECT Name, Sequence FROM Term WHERE Term.Name = $1", &[&name])
+            .map_err(postgres_error)?;
+        row.map(|row| term_from_row(&row)).transpose()
+    }
+
+    fn search_student(&mut self, id: u32) -> Result<Option<Student>> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT ID, LName, FName, Phone FROM Student WHERE Student.ID = $1",
+                &[&i64::from(id)],
+            )
+            .map_err(postgres_error)?;
+        row.map(|row| student_from_row(&row)).transpose()
+    }
+
+    fn search_students_by_name(&mut self, lname: &str, fname: &str) -> Result<Vec<Student>> {
+        let rows = self
+            .client
+            .query(
+                r"SELECT ID, LName, FName, Phone FROM Student
+                WHERE Student.LName = $1 AND Student.FName = $2
+                ORDER BY Student.ID",
+                &[&lname, &fname],
+            )
+            .map_err(postgres_error)?;
+        rows.iter().map(student_from_row).collect()
+    }
+    // End search functions
+
+    // Start update functions
+    fn update_course(&mut self, old: &Course, course: &Course) -> Result<()> {
+        self.client
+            .execute(
+                r"UPDATE Course SET Prefix = $1, Number = $2, Title = $3, Credits = $4
+                WHERE Course.Prefix = $5 AND Course.Number = $6",
+                &[
+                    &course.prefix,
+                    &course.number,
+                    &course.title,
+                    &i16::from(course.credits),
+                    &old.prefix,
+                    &old.number,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn update_grade(&mut self, old: &Grade, grade: &Grade) -> Result<()> {
+        self.client
+            .execute(
+                r"UPDATE Grade SET Letter = $1, Value = $2, CountsTowardGpa = $3, EarnsCredit = $4, Attempted = $5
+                WHERE Grade.Letter = $6",
+                &[
+                    &grade.letter,
+                    &grade.value,
+                    &grade.counts_toward_gpa,
+                    &grade.earns_credit,
+                    &grade.attempted,
+                    &old.letter,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn update_semester(&mut self, old: &Semester, semester: &Semester) -> Result<()> {
+        let year = smallint(semester.year)?;
+        self.client
+            .execute(
+                r"UPDATE Semester SET Code = $1, Year = $2, Description = $3, StartDate = $4, EndDate = $5
+                WHERE Semester.Code = $6",
+                &[
+                    &semester.code,
+                    &year,
+                    &semester.description,
+                    &semester.start_date,
+                    &semester.end_date,
+                    &old.code,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn update_term(&mut self, old: &Term, term: &Term) -> Result<()> {
+        // Start a transaction
+        let mut transaction = self.client.transaction().map_err(postgres_error)?;
+        transaction
+            .execute(
+                r"UPDATE Term SET Name = $1, Sequence = $2
+                WHERE Term.Name = $3",
+                &[&term.name, &i16::from(term.sequence), &old.name],
+            )
+            .map_err(postgres_error)?;
+        // Semesters name their term by description, so rename them in the same transaction
+        transaction
+            .execute(
+                "UPDATE Semester SET Description = $1 WHERE Semester.Description = $2",
+                &[&term.name, &old.name],
+            )
+            .map_err(postgres_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(postgres_error)
+    }
+
+    fn update_student(&mut self, old: &Student, student: &Student) -> Result<()> {
+        // Start a transaction
+        let mut transaction = self.client.transaction().map_err(postgres_error)?;
+        transaction
+            .execute(
+                r"UPDATE Student SET ID = $1, LName = $2, FName = $3, Phone = $4
+                WHERE Student.ID = $5",
+                &[
+                    &i64::from(student.id),
+                    &student.lname,
+                    &student.fname,
+                    &student.phone,
+                    &i64::from(old.id),
+                ],
+            )
+            .map_err(postgres_error)?;
+        // Move the sequence past the new ID so it is not handed out again
+        if student.id > old.id {
+            transaction
+                .execute(
+                    "SELECT setval(pg_get_serial_sequence('student', 'id'), MAX(ID)) FROM Student",
+                    &[],
+                )
+                .map_err(postgres_error)?;
+        }
+        // Commit the transaction
+        transaction.commit().map_err(postgres_error)
+    }
+
+    fn update_taken_course(&mut self, old: &TakenCourse, taken_course: &TakenCourse) -> Result<()> {
+        self.client
+            .execute(
+                r"UPDATE TakenCourse
+                SET StudentID = $1, CoursePrefix = $2, CourseNumber = $3, GradeLetter = $4, SemesterCode = $5
+                WHERE TakenCourse.StudentID = $6 AND TakenCourse.CoursePrefix = $7 AND TakenCourse.CourseNumber = $8
+                AND TakenCourse.GradeLetter = $9 AND TakenCourse.SemesterCode = $10",
+                &[
+                    &i64::from(taken_course.student_id),
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                    &i64::from(old.student_id),
+                    &old.course_prefix,
+                    &old.course_number,
+                    &old.grade_letter,
+                    &old.semester_code,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+    // End update functions
+
+    // Start delete functions
+    fn dependent_taken_courses(&mut self, item: Referenced) -> Result<Vec<TakenCourse>> {
+        let (_, condition, key) = item_key(item);
+        let key: Vec<&(dyn ToSql + Sync)> = key.iter().map(|value| value.as_ref()).collect();
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                    FROM TakenCourse
+                    WHERE {condition}"
+                ),
+                &key,
+            )
+            .map_err(postgres_error)?;
+        rows.iter().map(taken_course_from_row).collect()
+    }
+
+    fn delete_item(&mut self, item: Referenced, policy: DeletePolicy) -> Result<usize> {
+        let (delete, condition, key) = item_key(item);
+        let key: Vec<&(dyn ToSql + Sync)> = key.iter().map(|value| value.as_ref()).collect();
+        // Start a transaction
+        let mut transaction = self.client.transaction().map_err(postgres_error)?;
+        let row = transaction
+            .query_one(&format!("SELECT COUNT(*) FROM TakenCourse WHERE {condition}"), &key)
+            .map_err(postgres_error)?;
+        let dependents = unsigned::<i64, usize>(&row, 0)?;
+        if dependents > 0 {
+            if policy == DeletePolicy::Refuse {
+                return Err(still_referenced(dependents));
+            }
+            transaction
+                .execute(&format!("DELETE FROM TakenCourse WHERE {condition}"), &key)
+                .map_err(postgres_error)?;
+        }
+        transaction.execute(delete, &key).map_err(postgres_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(postgres_error)?;
+        Ok(dependents)
+    }
+
+    fn delete_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.client
+            .execute(
+                r"DELETE FROM TakenCourse
+                WHERE TakenCourse.StudentID = $1 AND TakenCourse.CoursePrefix = $2 AND TakenCourse.CourseNumber = $3
+                AND TakenCourse.GradeLetter = $4 AND TakenCourse.SemesterCode = $5",
+                &[
+                    &i64::from(taken_course.student_id),
+                    &taken_course.course_prefix,
+                    &taken_course.course_number,
+                    &taken_course.grade_letter,
+                    &taken_course.semester_code,
+                ],
+            )
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+
+    fn delete_term(&mut self, term: &Term) -> Result<()> {
+        self.client
+            .execute("DELETE FROM Term WHERE Term.Name = $1", &[&term.name])
+            .map_err(postgres_error)?;
+        Ok(())
+    }
+    // End delete functions
+
+    // Start list functions
+    fn list_courses(&mut self) -> Result<Vec<Course>> {
+        // Select all rows from table
+        let rows = self.client.query("SELECT * FROM Course", &[]).map_err(postgres_error)?;
+        rows.iter().map(course_from_row).collect()
+    }
+
+    fn list_grades(&mut self) -> Result<Vec<Grade>> {
+        // Select all rows from table
+        let rows = self
+            .client
+            .query("SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade", &[])
+            .map_err(postgres_error)?;
+        rows.iter().map(grade_from_row).collect()
+    }
+
+    fn list_semesters(&mut self) -> Result<Vec<Semester>> {
+        // Select all rows from table
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate
+                    FROM Semester {SEMESTER_TERM_JOIN}
+                    ORDER BY {SEMESTER_ORDER}"
+                ),
+                &[],
+            )
+            .map_err(postgres_error)?;
+        rows.iter().map(semester_from_row).collect()
+    }
+
+    fn list_terms(&mut self) -> Result<Vec<Term>> {
+        // Select all rows from table
+        let rows = self
+            .client
+            .query("SELECT Name, Sequence FROM Term ORDER BY Term.Sequence, Term.Name", &[])
+            .map_err(postgres_error)?;
+        rows.iter().map(term_from_row).collect()
+    }
+
+    fn list_students(&mut self) -> Result<Vec<Student>> {
+        // Select all rows from table
+        let rows = self
+            .client
+            .query("SELECT ID, LName, FName, Phone FROM Student", &[])
+            .map_err(postgres_error)?;
+        rows.iter().map(student_from_row).collect()
+    }
+
+    fn list_taken_courses(&mut self) -> Result<Vec<TakenCourse>> {
+        // Select all rows from table
+        let rows = self
+            .client
+            .query(
+                r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse",
+                &[],
+            )
+            .map_err(postgres_error)?;
+        rows.iter().map(taken_course_from_row).collect()
+    }
+    // End list functions
+
+    fn transcript(&mut self, student: &Student) -> Result<Vec<TranscriptRow>> {
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate,
+                    Course.Prefix, Course.Number, Course.Title, Course.Credits,
+                    Grade.Letter, Grade.Value, Grade.CountsTowardGpa, Grade.EarnsCredit, Grade.Attempted
+                    FROM TakenCourse
+                    JOIN Semester ON TakenCourse.SemesterCode = Semester.Code
+                    JOIN Course ON TakenCourse.CoursePrefix = Course.Prefix AND TakenCourse.CourseNumber = Course.Number
+                    JOIN Grade ON TakenCourse.GradeLetter = Grade.Letter
+                    {SEMESTER_TERM_JOIN}
+                    WHERE TakenCourse.StudentID = $1
+                    ORDER BY {SEMESTER_ORDER}, Course.Prefix, Course.Number"
+                ),
+                &[&i64::from(student.id)],
+            )
+            .map_err(postgres_error)?;
+        rows.iter()
+            .map(|row| {
+                Ok(TranscriptRow {
+                    semester: semester_from_row(row)?,
+                    course: Course {
+                        prefix: column(row, 5)?,
+                        number: column(row, 6)?,
+                        title: column(row, 7)?,
+                        credits: unsigned::<i16, u8>(row, 8)?,
+                    },
+                    grade: Grade {
+                        letter: column(row, 9)?,
+                        value: column(row, 10)?,
+                        counts_toward_gpa: column(row, 11)?,
+                        earns_credit: column(row, 12)?,
+                        attempted: column(row, 13)?,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the statement that deletes the given item, the condition that picks out the taken courses
+/// that reference it, and the item's key which both of them take as parameters.
+fn item_key(item: Referenced) -> (&'static str, &'static str, Vec<Box<dyn ToSql + Sync>>) {
+    match item {
+        Referenced::Course(course) => (
+            "DELETE FROM Course WHERE Course.Prefix = $1 AND Course.Number = $2",
+            "TakenCourse.CoursePrefix = $1 AND TakenCourse.CourseNumber = $2",
+            vec![Box::new(course.prefix.clone()), Box::new(course.number.clone())],
+        ),
+        Referenced::Grade(grade) => (
+            "DELETE FROM Grade WHERE Grade.Letter = $1",
+            "TakenCourse.GradeLetter = $1",
+            vec![Box::new(grade.letter.clone())],
+        ),
+        Referenced::Semester(semester) => (
+            "DELETE FROM Semester WHERE Semester.Code = $1",
+            "TakenCourse.SemesterCode = $1",
+            vec![Box::new(semester.code.clone())],
+        ),
+        Referenced::Student(student) => (
+            "DELETE FROM Student WHERE Student.ID = $1",
+            "TakenCourse.StudentID = $1",
+            vec![Box::new(i64::from(student.id))],
+        ),
+    }
+}
+
+/// Reads a column, failing instead of panicking when it holds something other than what is expected.
+fn column<'a, T: FromSql<'a>>(row: &'a Row, index: usize) -> Result<T> {
+    row.try_get(index)
+        .map_err(|error| RegistrarError::Internal(format!("Unable to read column {index}. {error}")))
+}
+
+/// Reads a column into one of the unsigned types the items use. PostgreSQL has no unsigned types so
+/// the numbers are stored in signed columns, and one out of range was changed outside the program.
+fn unsigned<'a, S, U>(row: &'a Row, index: usize) -> Result<U>
+where
+    S: FromSql<'a> + Copy + Display,
+    U: TryFrom<S>,
+{
+    let value: S = column(row, index)?;
+    U::try_from(value)
+        .map_err(|_| RegistrarError::Internal(format!("Unable to read column {index}. {value} is out of range.")))
+}
+
+/// Converts a semester year to the smallint column it is stored in.
+fn smallint(year: u16) -> Result<i16> {
+    i16::try_from(year).map_err(|_| RegistrarError::Internal(format!("Semester year {year} is out of range.")))
+}
+
+fn course_from_row(row: &Row) -> Result<Course> {
+    Ok(Course {
+        prefix: column(row, 0)?,
+        number: column(row, 1)?,
+        title: column(row, 2)?,
+        credits: unsigned::<i16, u8>(row, 3)?,
+    })
+}
+
+fn grade_from_row(row: &Row) -> Result<Grade> {
+    Ok(Grade {
+        letter: column(row, 0)?,
+        value: column(row, 1)?,
+        counts_toward_gpa: column(row, 2)?,
+        earns_credit: column(row, 3)?,
+        attempted: column(row, 4)?,
+    })
+}
+
+fn semester_from_row(row: &Row) -> Result<Semester> {
+    Ok(Semester {
+        code: column(row, 0)?,
+        year: unsigned::<i16, u16>(row, 1)?,
+        description: column(row, 2)?,
+        start_date: column(row, 3)?,
+        end_date: column(row, 4)?,
+    })
+}
+
+fn term_from_row(row: &Row) -> Result<Term> {
+    Ok(Term {
+        name: column(row, 0)?,
+        sequence: unsigned::<i16, u8>(row, 1)?,
+    })
+}
+
+fn student_from_row(row: &Row) -> Result<Student> {
+    Ok(Student {
+        id: unsigned::<i64, u32>(row, 0)?,
+        lname: column(row, 1)?,
+        fname: column(row, 2)?,
+        phone: column(row, 3)?,
+    })
+}
+
+fn taken_course_from_row(row: &Row) -> Result<TakenCourse> {
+    Ok(TakenCourse {
+        student_id: unsigned::<i64, u32>(row, 0)?,
+        course_prefix: column(row, 1)?,
+        course_number: column(row, 2)?,
+        grade_letter: column(row, 3)?,
+        semester_code: column(row, 4)?,
+    })
+}
@@ -0,0 +1,738 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{date_from_str, Course, Grade, Semester, Student, TakenCourse, Term, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::migrations::{Direction, Migration};
+use crate::store::{
+    still_referenced, DeletePolicy, Reference, Referenced, RegistrarStore, SEMESTER_ORDER, SEMESTER_TERM_JOIN,
+};
+use rusqlite::types::{ToSql, Type, Value, ValueRef};
+use rusqlite::{ffi, params, params_from_iter, Connection, OptionalExtension};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use time::Date;
+
+/// SQLite implementation of the registrar store. Keeps the whole database in a single local file
+/// so the program can be used without a MySQL server.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the SQLite database at the given path, creating the file if it does not exist.
+    pub fn open(path: &str) -> Result<SqliteStore> {
+        let conn = Connection::open(path).map_err(|error| {
+            RegistrarError::Connection(format!("Unable to open the SQLite database '{path}'. {error}"))
+        })?;
+        // SQLite only enforces foreign keys when asked to on each connection
+        conn.execute_batch("PRAGMA foreign_keys = ON").map_err(sqlite_error)?;
+        Ok(SqliteStore { conn })
+    }
+}
+
+/// Sorts a SQLite error by what went wrong. Unique constraint violations are duplicates, other
+/// constraint violations break a reference, and a database file that cannot be opened or is locked
+/// is a connection problem.
+fn sqlite_error(error: rusqlite::Error) -> RegistrarError {
+    match error {
+        rusqlite::Error::SqliteFailure(ref failure, _)
+            if failure.extended_code == ffi::SQLITE_CONSTRAINT_PRIMARYKEY
+                || failure.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE =>
+        {
+            RegistrarError::Duplicate(error.to_string())
+        }
+        rusqlite::Error::SqliteFailure(ref failure, _) => match failure.code {
+            ffi::ErrorCode::ConstraintViolation => RegistrarError::Constraint(error.to_string()),
+            ffi::ErrorCode::CannotOpen
+            | ffi::ErrorCode::DatabaseBusy
+            | ffi::ErrorCode::DatabaseLocked
+            | ffi::ErrorCode::NotADatabase
+            | ffi::ErrorCode::PermissionDenied
+            | ffi::ErrorCode::ReadOnly => RegistrarError::Connection(error.to_string()),
+            _ => RegistrarError::Internal(error.to_string()),
+        },
+        _ => RegistrarError::Internal(error.to_string()),
+    }
+}
+
+impl RegistrarStore for SqliteStore {
+    // Start migration functions
+    fn schema_version(&mut self) -> Result<u32> {
+        // Create the table that tracks applied migrations if it does not exist
+        self.conn
+            .execute(
+                r"CREATE TABLE IF NOT EXISTS SchemaVersion (
+                Version integer not null,
+                Description nvarchar(64) not null,
+                PRIMARY KEY (Version))",
+                [],
+            )
+            .map_err(sqlite_error)?;
+        self.conn
+            .query_row("SELECT COALESCE(MAX(Version), 0) FROM SchemaVersion", [], |row| row.get(0))
+            .map_err(sqlite_error)
+    }
+
+    fn apply_migration(&mut self, migration: &Migration, direction: Direction) -> Result<()> {
+        let statements = match direction {
+            Direction::Up => migration.up.sqlite,
+            Direction::Down => migration.down.sqlite,
+        };
+        // Foreign keys are turned off while tables are rebuilt so dropping a table does not cascade.
+        // This cannot be changed inside of a transaction.
+        self.conn.execute_batch("PRAGMA foreign_keys = OFF").map_err(sqlite_error)?;
+        let result = run_migration(&mut self.conn, migration, direction, statements);
+        self.conn.execute_batch("PRAGMA foreign_keys = ON").map_err(sqlite_error)?;
+        result
+    }
+    // End migration functions
+
+    // Start insert functions
+    fn insert_course(&mut self, course: &Course) -> Result<()> {
+        self.conn
+            .execute(
+                r"INSERT INTO Course (Prefix, Number, Title, Credits)
+                VALUES (?, ?, ?, ?)",
+                params![course.prefix, course.number, course.title, course.credits],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn insert_grade(&mut self, grade: &Grade) -> Result<()> {
+        self.conn
+            .execute(
+                r"INSERT INTO Grade (Letter, Value, CountsTowardGpa, EarnsCredit, Attempted)
+                VALUES (?, ?, ?, ?, ?)",
+                params![
+                    grade.letter,
+                    grade.value.to_string(),
+                    grade.counts_toward_gpa,
+                    grade.earns_credit,
+                    grade.attempted,
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn insert_semester(&mut self, semester: &Semester) -> Result<()> {
+        self.conn
+            .execute(
+                r"INSERT INTO Semester (Code, Year, Description, StartDate, EndDate)
+                VALUES (?, ?, ?, ?, ?)",
+                params![
+                    semester.code,
+                    semester.year,
+                    semester.description,
+                    semester.start_date.map(|date| date.to_string()),
+                    semester.end_date.map(|date| date.to_string()),
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn insert_term(&mut self, term: &Term) -> Result<()> {
+        self.conn
+            .execute(
+                r"INSERT INTO Term (Name, Sequence)
+                VALUES (?, ?)",
+                params![term.name, term.sequence],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn insert_student(&mut self, student: &Student) -> Result<u32> {
+        // Inserting a null ID has SQLite pick the next one
+        let id = if student.id == 0 { None } else { Some(student.id) };
+        self.conn
+            .execute(
+                r"INSERT INTO Student (ID, LName, FName, Phone)
+                VALUES (?, ?, ?, ?)",
+                params![id, student.lname, student.fname, student.phone],
+            )
+            .map_err(sqlite_error)?;
+        u32::try_from(self.conn.last_insert_rowid())
+            .map_err(|_| RegistrarError::Internal(String::from("The new student's ID is out of range.")))
+    }
+
+    fn insert_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        write_taken_course(
+            &mut self.conn,
+            taken_course,
+            r"INSERT INTO TakenCourse (
+                StudentID,
+                CoursePrefix,
+                CourseNumber,
+                GradeLetter,
+                SemesterCode)
+            VALUES (?, ?, ?, ?, ?)",
+            params![
+                taken_course.student_id,
+                taken_course.course_prefix,
+                taken_course.course_number,
+                taken_course.grade_letter,
+                taken_course.semester_code,
+            ],
+        )
+    }
+    // End insert functions
+
+    // Start search functions
+    fn search_course(&mut self, prefix: &str, number: &str) -> Result<Option<Course>> {
+        self.conn
+            .query_row(
+                "SELECT * FROM Course WHERE Course.Prefix = ? AND Course.Number = ?",
+                params![prefix, number],
+                |row| {
+                    Ok(Course {
+                        prefix: row.get(0)?,
+                        number: row.get(1)?,
+                        title: row.get(2)?,
+                        credits: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    fn search_grade(&mut self, letter: &str) -> Result<Option<Grade>> {
+        self.conn
+            .query_row(
+                r"SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade
+                WHERE Grade.Letter = ?",
+                params![letter],
+                |row| grade_from_row(row, 0),
+            )
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    fn search_semester(&mut self, code: &str) -> Result<Option<Semester>> {
+        self.conn
+            .query_row(
+                r"SELECT Code, Year, Description, StartDate, EndDate FROM Semester
+                WHERE Semester.Code = ?",
+                params![code],
+                semester_from_row,
+            )
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    fn search_term(&mut self, name: &str) -> Result<Option<Term>> {
+        self.conn
+            .query_row(
+                "SELECT Name, Sequence FROM Term WHERE Term.Name = ?",
+                params![name],
+                term_from_row,
+            )
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    fn search_student(&mut self, id: u32) -> Result<Option<Student>> {
+        self.conn
+            .query_row(
+                "SELECT ID, LName, FName, Phone FROM Student WHERE Student.ID = ?",
+                params![id],
+                student_from_row,
+            )
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    fn search_students_by_name(&mut self, lname: &str, fname: &str) -> Result<Vec<Student>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r"SELECT ID, LName, FName, Phone FROM Student
+                WHERE Student.LName = ? AND Student.FName = ?
+                ORDER BY Student.ID",
+            )
+            .map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(params![lname, fname], student_from_row)
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+    // End search functions
+
+    // Start update functions
+    fn update_course(&mut self, old: &Course, course: &Course) -> Result<()> {
+        self.conn
+            .execute(
+                r"UPDATE Course SET Prefix = ?, Number = ?, Title = ?, Credits = ?
+                WHERE Course.Prefix = ? AND Course.Number = ?",
+                params![course.prefix, course.number, course.title, course.credits, old.prefix, old.number],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn update_grade(&mut self, old: &Grade, grade: &Grade) -> Result<()> {
+        self.conn
+            .execute(
+                r"UPDATE Grade SET Letter = ?, Value = ?, CountsTowardGpa = ?, EarnsCredit = ?, Attempted = ?
+                WHERE Grade.Letter = ?",
+                params![
+                    grade.letter,
+                    grade.value.to_string(),
+                    grade.counts_toward_gpa,
+                    grade.earns_credit,
+                    grade.attempted,
+                    old.letter,
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn update_semester(&mut self, old: &Semester, semester: &Semester) -> Result<()> {
+        self.conn
+            .execute(
+                r"UPDATE Semester SET Code = ?, Year = ?, Description = ?, StartDate = ?, EndDate = ?
+                WHERE Semester.Code = ?",
+                params![
+                    semester.code,
+                    semester.year,
+                    semester.description,
+                    semester.start_date.map(|date| date.to_string()),
+                    semester.end_date.map(|date| date.to_string()),
+                    old.code,
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn update_term(&mut self, old: &Term, term: &Term) -> Result<()> {
+        // Start a transaction
+        let transaction = self.conn.transaction().map_err(sqlite_error)?;
+        transaction
+            .execute(
+                r"UPDATE Term SET Name = ?, Sequence = ?
+                WHERE Term.Name = ?",
+                params![term.name, term.sequence, old.name],
+            )
+            .map_err(sqlite_error)?;
+        // Semesters name their term by description, so rename them in the same transaction
+        transaction
+            .execute(
+                "UPDATE Semester SET Description = ? WHERE Semester.Description = ?",
+                params![term.name, old.name],
+            )
+            .map_err(sqlite_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    fn update_student(&mut self, old: &Student, student: &Student) -> Result<()> {
+        self.conn
+            .execute(
+                r"UPDATE Student SET ID = ?, LName = ?, FName = ?, Phone = ?
+                WHERE Student.ID = ?",
+                params![student.id, student.lname, student.fname, student.phone, old.id],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn update_taken_course(&mut self, old: &TakenCourse, taken_course: &TakenCourse) -> Result<()> {
+        write_taken_course(
+            &mut self.conn,
+            taken_course,
+            r"UPDATE TakenCourse
+            SET StudentID = ?, CoursePrefix = ?, CourseNumber = ?, GradeLetter = ?, SemesterCode = ?
+            WHERE TakenCourse.StudentID = ? AND TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?
+            AND TakenCourse.GradeLetter = ? AND TakenCourse.SemesterCode = ?",
+            params![
+                taken_course.student_id,
+                taken_course.course_prefix,
+                taken_course.course_number,
+                taken_course.grade_letter,
+                taken_course.semester_code,
+                old.student_id,
+                old.course_prefix,
+                old.course_number,
+                old.grade_letter,
+                old.semester_code,
+            ],
+        )
+    }
+    // End update functions
+
+    // Start delete functions
+    fn dependent_taken_courses(&mut self, item: Referenced) -> Result<Vec<TakenCourse>> {
+        let (_, condition, key) = item_key(item);
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse
+                WHERE {condition}"
+            ))
+            .map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(key.iter()), taken_course_from_row)
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn delete_item(&mut self, item: Referenced, policy: DeletePolicy) -> Result<usize> {
+        let (delete, condition, key) = item_key(item);
+        // Start a transaction
+        let transaction = self.conn.transaction().map_err(sqlite_error)?;
+        let dependents: usize = transaction
+            .query_row(
+                &format!("SELECT COUNT(*) FROM TakenCourse WHERE {condition}"),
+                params_from_iter(key.iter()),
+                |row| row.get(0),
+            )
+            .map_err(sqlite_error)?;
+        if dependents > 0 {
+            if policy == DeletePolicy::Refuse {
+                return Err(still_referenced(dependents));
+            }
+            transaction
+                .execute(
+                    &format!("DELETE FROM TakenCourse WHERE {condition}"),
+                    params_from_iter(key.iter()),
+                )
+                .map_err(sqlite_error)?;
+        }
+        transaction.execute(delete, params_from_iter(key.iter())).map_err(sqlite_error)?;
+        // Commit the transaction
+        transaction.commit().map_err(sqlite_error)?;
+        Ok(dependents)
+    }
+
+    fn delete_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()> {
+        self.conn
+            .execute(
+                r"DELETE FROM TakenCourse
+                WHERE TakenCourse.StudentID = ? AND TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?
+                AND TakenCourse.GradeLetter = ? AND TakenCourse.SemesterCode = ?",
+                params![
+                    taken_course.student_id,
+                    taken_course.course_prefix,
+                    taken_course.course_number,
+                    taken_course.grade_letter,
+                    taken_course.semester_code,
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn delete_term(&mut self, term: &Term) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM Term WHERE Term.Name = ?", params![term.name])
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+    // End delete functions
+
+    // Start list functions
+    fn list_courses(&mut self) -> Result<Vec<Course>> {
+        // Select all rows from table
+        let mut stmt = self.conn.prepare("SELECT * FROM Course").map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Course {
+                    prefix: row.get(0)?,
+                    number: row.get(1)?,
+                    title: row.get(2)?,
+                    credits: row.get(3)?,
+                })
+            })
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn list_grades(&mut self) -> Result<Vec<Grade>> {
+        // Select all rows from table
+        let mut stmt = self
+            .conn
+            .prepare("SELECT Letter, Value, CountsTowardGpa, EarnsCredit, Attempted FROM Grade")
+            .map_err(sqlite_error)?;
+        let rows = stmt.query_map([], |row| grade_from_row(row, 0)).map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn list_semesters(&mut self) -> Result<Vec<Semester>> {
+        // Select all rows from table
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate
+                FROM Semester {SEMESTER_TERM_JOIN}
+                ORDER BY {SEMESTER_ORDER}"
+            ))
+            .map_err(sqlite_error)?;
+        let rows = stmt.query_map([], semester_from_row).map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn list_terms(&mut self) -> Result<Vec<Term>> {
+        // Select all rows from table
+        let mut stmt = self
+            .conn
+            .prepare("SELECT Name, Sequence FROM Term ORDER BY Term.Sequence, Term.Name")
+            .map_err(sqlite_error)?;
+        let rows = stmt.query_map([], term_from_row).map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn list_students(&mut self) -> Result<Vec<Student>> {
+        // Select all rows from table
+        let mut stmt = self
+            .conn
+            .prepare("SELECT ID, LName, FName, Phone FROM Student")
+            .map_err(sqlite_error)?;
+        let rows = stmt.query_map([], student_from_row).map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+
+    fn list_taken_courses(&mut self) -> Result<Vec<TakenCourse>> {
+        // Select all rows from table
+        let mut stmt = self
+            .conn
+            .prepare(
+                r"SELECT StudentID, CoursePrefix, CourseNumber, GradeLetter, SemesterCode
+                FROM TakenCourse",
+            )
+            .map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map([], taken_course_from_row)
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+    // End list functions
+
+    fn transcript(&mut self, student: &Student) -> Result<Vec<TranscriptRow>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                r"SELECT Semester.Code, Semester.Year, Semester.Description, Semester.StartDate, Semester.EndDate,
+                Course.Prefix, Course.Number, Course.Title, Course.Credits,
+                Grade.Letter, Grade.Value, Grade.CountsTowardGpa, Grade.EarnsCredit, Grade.Attempted
+                FROM TakenCourse
+                JOIN Semester ON TakenCourse.SemesterCode = Semester.Code
+                JOIN Course ON TakenCourse.CoursePrefix = Course.Prefix AND TakenCourse.CourseNumber = Course.Number
+                JOIN Grade ON TakenCourse.GradeLetter = Grade.Letter
+                {SEMESTER_TERM_JOIN}
+                WHERE TakenCourse.StudentID = ?
+                ORDER BY {SEMESTER_ORDER}, Course.Prefix, Course.Number"
+            ))
+            .map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(params![student.id], |row| {
+                Ok(TranscriptRow {
+                    semester: semester_from_row(row)?,
+                    course: Course {
+                        prefix: row.get(5)?,
+                        number: row.get(6)?,
+                        title: row.get(7)?,
+                        credits: row.get(8)?,
+                    },
+                    grade: grade_from_row(row, 9)?,
+                })
+            })
+            .map_err(sqlite_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sqlite_error)
+    }
+}
+
+fn run_migration(
+    conn: &mut Connection,
+    migration: &Migration,
+    direction: Direction,
+    statements: &[&str],
+) -> Result<()> {
+    // Start a transaction so a failed migration leaves the schema untouched
+    let transaction = conn.transaction().map_err(sqlite_error)?;
+    for statement in statements.iter() {
+        transaction.execute_batch(statement).map_err(sqlite_error)?;
+    }
+    // Make sure the rebuilt tables did not leave any broken references behind
+    let broken = transaction
+        .prepare("PRAGMA foreign_key_check")
+        .and_then(|mut stmt| stmt.exists([]))
+        .map_err(sqlite_error)?;
+    if broken {
+        return Err(RegistrarError::Constraint(String::from(
+            "Unable to migrate database. Existing rows reference items that do not exist.",
+        )));
+    }
+    // Record the new schema version
+    match direction {
+        Direction::Up => transaction.execute(
+            "INSERT INTO SchemaVersion (Version, Description) VALUES (?, ?)",
+            params![migration.version, migration.description],
+        ),
+        Direction::Down => transaction.execute(
+            "DELETE FROM SchemaVersion WHERE Version = ?",
+            params![migration.version],
+        ),
+    }
+    .map_err(sqlite_error)?;
+    // Commit the transaction
+    transaction.commit().map_err(sqlite_error)
+}
+
+/// Returns the statement that deletes the given item, the condition that picks out the taken courses
+/// that reference it, and the item's key which both of them take as parameters.
+fn item_key(item: Referenced) -> (&'static str, &'static str, Vec<Value>) {
+    match item {
+        Referenced::Course(course) => (
+            "DELETE FROM Course WHERE Course.Prefix = ? AND Course.Number = ?",
+            "TakenCourse.CoursePrefix = ? AND TakenCourse.CourseNumber = ?",
+            vec![Value::Text(course.prefix.clone()), Value::Text(course.number.clone())],
+        ),
+        Referenced::Grade(grade) => (
+            "DELETE FROM Grade WHERE Grade.Letter = ?",
+            "TakenCourse.GradeLetter = ?",
+            vec![Value::Text(grade.letter.clone())],
+        ),
+        Referenced::Semester(semester) => (
+            "DELETE FROM Semester WHERE Semester.Code = ?",
+            "TakenCourse.SemesterCode = ?",
+            vec![Value::Text(semester.code.clone())],
+        ),
+        Referenced::Student(student) => (
+            "DELETE FROM Student WHERE Student.ID = ?",
+            "TakenCourse.StudentID = ?",
+            vec![Value::Integer(i64::from(student.id))],
+        ),
+    }
+}
+
+/// Runs a statement that writes the given taken course into the TakenCourse table.
+fn write_taken_course(
+    conn: &mut Connection,
+    taken_course: &TakenCourse,
+    sql: &str,
+    params: &[&dyn ToSql],
+) -> Result<()> {
+    // Start a transaction
+    let transaction = conn.transaction().map_err(sqlite_error)?;
+    match transaction.execute(sql, params) {
+        // SQLite does not say which foreign key failed so look for the missing row before the
+        // transaction is rolled back
+        Err(rusqlite::Error::SqliteFailure(failure, _))
+            if failure.extended_code == ffi::SQLITE_CONSTRAINT_FOREIGNKEY =>
+        {
+            match missing_reference(&transaction, taken_course).map_err(sqlite_error)? {
+                Some(reference) => Err(reference.missing()),
+                None => Err(sqlite_error(rusqlite::Error::SqliteFailure(failure, None))),
+            }
+        }
+        Err(error) => Err(sqlite_error(error)),
+        // Commit the transaction
+        Ok(_) => transaction.commit().map_err(sqlite_error),
+    }
+}
+
+/// Finds which row referenced by the given taken course does not exist.
+fn missing_reference(conn: &Connection, taken_course: &TakenCourse) -> rusqlite::Result<Option<Reference>> {
+    if !conn
+        .prepare("SELECT 1 FROM Student WHERE ID = ?")?
+        .exists([taken_course.student_id])?
+    {
+        return Ok(Some(Reference::Student));
+    }
+    if !conn
+        .prepare("SELECT 1 FROM Course WHERE Prefix = ? AND Number = ?")?
+        .exists([&taken_course.course_prefix, &taken_course.course_number])?
+    {
+        return Ok(Some(Reference::Course));
+    }
+    if !conn.prepare("SELECT 1 FROM Grade WHERE Letter = ?")?.exists([&taken_course.grade_letter])? {
+        return Ok(Some(Reference::Grade));
+    }
+    if !conn.prepare("SELECT 1 FROM Semester WHERE Code = ?")?.exists([&taken_course.semester_code])? {
+        return Ok(Some(Reference::Semester));
+    }
+    Ok(None)
+}
+
+/// Reads a decimal column. SQLite keeps numeric values as integers or floating point numbers
+/// depending on what fits, so both have to be handled.
+fn decimal_from_row(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Decimal> {
+    match row.get_ref(index)? {
+        ValueRef::Integer(value) => Ok(Decimal::from(value)),
+        ValueRef::Real(value) => Decimal::from_f64(value)
+            .map(|value| value.round_dp(2))
+            .ok_or(rusqlite::Error::InvalidColumnType(index, String::from("Value"), Type::Real)),
+        ValueRef::Text(value) => String::from_utf8_lossy(value)
+            .parse()
+            .map_err(|error| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(error))),
+        other => Err(rusqlite::Error::InvalidColumnType(index, String::from("Value"), other.data_type())),
+    }
+}
+
+/// Reads an optional date column. SQLite has no date type so dates are kept as YYYY-MM-DD text.
+fn date_from_row(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Option<Date>> {
+    let text: Option<String> = row.get(index)?;
+    let text = match text {
+        Some(text) => text,
+        None => return Ok(None),
+    };
+    date_from_str(&text).map(Some).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(
+            index,
+            Type::Text,
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid date '{text}'"))),
+        )
+    })
+}
+
+/// Reads a grade from the row starting at the given column.
+fn grade_from_row(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Grade> {
+    Ok(Grade {
+        letter: row.get(index)?,
+        value: decimal_from_row(row, index + 1)?,
+        counts_toward_gpa: row.get(index + 2)?,
+        earns_credit: row.get(index + 3)?,
+        attempted: row.get(index + 4)?,
+    })
+}
+
+fn semester_from_row(row: &rusqlite::Row) -> rusqlite::Result<Semester> {
+    Ok(Semester {
+        code: row.get(0)?,
+        year: row.get(1)?,
+        description: row.get(2)?,
+        start_date: date_from_row(row, 3)?,
+        end_date: date_from_row(row, 4)?,
+    })
+}
+
+fn term_from_row(row: &rusqlite::Row) -> rusqlite::Result<Term> {
+    Ok(Term {
+        name: row.get(0)?,
+        sequence: row.get(1)?,
+    })
+}
+
+fn student_from_row(row: &rusqlite::Row) -> rusqlite::Result<Student> {
+    Ok(Student {
+        id: row.get(0)?,
+        lname: row.get(1)?,
+        fname: row.get(2)?,
+        phone: row.get(3)?,
+    })
+}
+
+fn taken_course_from_row(row: &rusqlite::Row) -> rusqlite::Result<TakenCourse> {
+    Ok(TakenCourse {
+        student_id: row.get(0)?,
+        course_prefix: row.get(1)?,
+        course_number: row.get(2)?,
+        grade_letter: row.get(3)?,
+        semester_code: row.get(4)?,
+    })
+}
@@ -0,0 +1,181 @@
+// Author: Grant Duchars
+use crate::config::Profile;
+use crate::dbmgmt::{Course, Grade, Semester, Student, TakenCourse, Term, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::memory_store::MemoryStore;
+use crate::migrations::{Direction, Migration};
+use crate::mysql_store::MySqlStore;
+use crate::postgres_store::PostgresStore;
+use crate::sqlite_store::SqliteStore;
+use mysql::{Opts, OptsBuilder};
+use std::path::Path;
+
+/// Storage operations the registrar needs from a database backend. The functions in dbmgmt only talk
+/// to the database through this trait so any backend can be plugged in.
+pub trait RegistrarStore {
+    // Start migration functions
+    /// Returns the version of the newest migration applied to the database, or 0 if none have been.
+    fn schema_version(&mut self) -> Result<u32>;
+    /// Runs this backend's statements for the given migration and records the new schema version.
+    fn apply_migration(&mut self, migration: &Migration, direction: Direction) -> Result<()>;
+    // End migration functions
+
+    // Start insert functions
+    fn insert_course(&mut self, course: &Course) -> Result<()>;
+    fn insert_grade(&mut self, grade: &Grade) -> Result<()>;
+    fn insert_semester(&mut self, semester: &Semester) -> Result<()>;
+    fn insert_term(&mut self, term: &Term) -> Result<()>;
+    /// Inserts the given student and returns their ID. An ID of 0 lets the database pick the next one.
+    fn insert_student(&mut self, student: &Student) -> Result<u32>;
+    fn insert_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()>;
+    // End insert functions
+
+    // Start search functions
+    fn search_course(&mut self, prefix: &str, number: &str) -> Result<Option<Course>>;
+    fn search_grade(&mut self, letter: &str) -> Result<Option<Grade>>;
+    fn search_semester(&mut self, code: &str) -> Result<Option<Semester>>;
+    fn search_term(&mut self, name: &str) -> Result<Option<Term>>;
+    fn search_student(&mut self, id: u32) -> Result<Option<Student>>;
+    /// Returns every student with the given name. More than one student can share a name.
+    fn search_students_by_name(&mut self, lname: &str, fname: &str) -> Result<Vec<Student>>;
+    // End search functions
+
+    // Start update functions
+    /// Replaces the old course with the new one. Changing the key updates the taken courses that use it.
+    fn update_course(&mut self, old: &Course, course: &Course) -> Result<()>;
+    fn update_grade(&mut self, old: &Grade, grade: &Grade) -> Result<()>;
+    fn update_semester(&mut self, old: &Semester, semester: &Semester) -> Result<()>;
+    /// Replaces the old term with the new one. Renaming the term renames the semesters described by it.
+    fn update_term(&mut self, old: &Term, term: &Term) -> Result<()>;
+    fn update_student(&mut self, old: &Student, student: &Student) -> Result<()>;
+    fn update_taken_course(&mut self, old: &TakenCourse, taken_course: &TakenCourse) -> Result<()>;
+    // End update functions
+
+    // Start delete functions
+    /// Returns the taken courses that reference the given item.
+    fn dependent_taken_courses(&mut self, item: Referenced) -> Result<Vec<TakenCourse>>;
+    /// Deletes the given item. With the Cascade policy the taken courses that reference it are deleted
+    /// in the same transaction, otherwise nothing is deleted while any exist. Returns how many taken
+    /// courses were deleted along with the item.
+    fn delete_item(&mut self, item: Referenced, policy: DeletePolicy) -> Result<usize>;
+    fn delete_taken_course(&mut self, taken_course: &TakenCourse) -> Result<()>;
+    fn delete_term(&mut self, term: &Term) -> Result<()>;
+    // End delete functions
+
+    // Start list functions
+    fn list_courses(&mut self) -> Result<Vec<Course>>;
+    fn list_grades(&mut self) -> Result<Vec<Grade>>;
+    /// Returns every semester in academic calendar order.
+    fn list_semesters(&mut self) -> Result<Vec<Semester>>;
+    /// Returns every term in the order they fall within a year.
+    fn list_terms(&mut self) -> Result<Vec<Term>>;
+    fn list_students(&mut self) -> Result<Vec<Student>>;
+    fn list_taken_courses(&mut self) -> Result<Vec<TakenCourse>>;
+    // End list functions
+
+    /// Returns every course the given student has taken with its semester and grade, ordered by
+    /// semester in academic calendar order and then by course.
+    fn transcript(&mut self, student: &Student) -> Result<Vec<TranscriptRow>>;
+}
+
+/// Whether the given item names a database instead of being a mistyped command. It has to have a
+/// scheme or be a SQLite file that already exists.
+pub fn is_url(item: &str) -> bool {
+    item.contains("://") || item == "--memory" || Path::new(item).is_file()
+}
+
+/// Opens the store described by the given url. The scheme picks the backend: mysql://, postgres://
+/// (or postgresql://), sqlite:// and memory://. A sqlite:// file is created if it does not exist, and
+/// a path without a scheme is only opened if the file is already there so a mistyped command never
+/// creates one. The TLS settings are added to MySQL urls.
+pub fn open(url: &str, settings: &Profile) -> Result<Box<dyn RegistrarStore>> {
+    if url.starts_with("mysql://") {
+        let opts = Opts::from_url(url).map_err(mysql::Error::UrlError)?;
+        // Only replace the url's own TLS options if the settings ask for TLS
+        let opts = match settings.ssl_opts() {
+            Some(ssl_opts) => OptsBuilder::from_opts(opts).ssl_opts(ssl_opts),
+            None => OptsBuilder::from_opts(opts),
+        };
+        Ok(Box::new(MySqlStore::connect(opts)?))
+    } else if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        Ok(Box::new(PostgresStore::connect(url)?))
+    } else if url == "memory://" || url == "--memory" {
+        Ok(Box::new(MemoryStore::new()))
+    } else if let Some(path) = url.strip_prefix("sqlite://") {
+        Ok(Box::new(SqliteStore::open(path)?))
+    } else if !url.contains("://") && Path::new(url).is_file() {
+        Ok(Box::new(SqliteStore::open(url)?))
+    } else {
+        Err(RegistrarError::Validation(format!(
+            "Unknown database url '{url}'. Use mysql://, postgres://, memory:// or sqlite://PATH, which creates a new SQLite file.",
+        )))
+    }
+}
+
+/// Joins each semester with the term its description names so semesters can be put in calendar order.
+pub const SEMESTER_TERM_JOIN: &str = "LEFT JOIN Term ON Term.Name = Semester.Description";
+
+/// Puts semesters in academic calendar order the same way Semester::calendar_key does.
+pub const SEMESTER_ORDER: &str =
+    "Semester.Year, COALESCE(Term.Sequence, 255), Semester.StartDate IS NULL, Semester.StartDate, Semester.Code";
+
+/// An item that taken courses can reference through their foreign keys.
+#[derive(Clone, Copy)]
+pub enum Referenced<'a> {
+    Course(&'a Course),
+    Grade(&'a Grade),
+    Semester(&'a Semester),
+    Student(&'a Student),
+}
+
+/// What to do with the taken courses that reference an item being deleted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Leave everything in place if any taken courses reference the item.
+    Refuse,
+    /// Delete the taken courses that reference the item along with it.
+    Cascade,
+}
+
+/// The error returned when an item cannot be deleted because taken courses still reference it.
+pub fn still_referenced(count: usize) -> RegistrarError {
+    RegistrarError::Constraint(format!("Unable to delete item. It is used by {count} taken course(s)."))
+}
+
+/// The rows a taken course references through its foreign keys.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    Student,
+    Course,
+    Grade,
+    Semester,
+}
+
+impl Reference {
+    /// Finds which foreign key of TakenCourse is named in the given constraint name or error message.
+    pub fn from_constraint(text: &str) -> Option<Reference> {
+        let text = text.to_lowercase();
+        if text.contains("fk_takencourse_student") {
+            Some(Reference::Student)
+        } else if text.contains("fk_takencourse_course") {
+            Some(Reference::Course)
+        } else if text.contains("fk_takencourse_grade") {
+            Some(Reference::Grade)
+        } else if text.contains("fk_takencourse_semester") {
+            Some(Reference::Semester)
+        } else {
+            None
+        }
+    }
+
+    /// The error shown when a taken course references a row that does not exist.
+    pub fn missing(self) -> RegistrarError {
+        let item = match self {
+            Reference::Student => "student",
+            Reference::Course => "course",
+            Reference::Grade => "grade",
+            Reference::Semester => "semester",
+        };
+        RegistrarError::Constraint(format!("Unable to save item to database. Given {item} does not exist."))
+    }
+}
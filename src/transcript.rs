@@ -0,0 +1,692 @@
+// Author: Grant Duchars
+use crate::dbmgmt::{Course, Grade, Semester, Student, TranscriptRow};
+use crate::error::{RegistrarError, Result};
+use crate::output::{self, Column, Format, Record};
+use crate::store::RegistrarStore;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Serialize;
+
+/// A student's transcript. The courses they have taken are grouped by semester in academic calendar
+/// order, and each semester and the transcript as a whole have their totals worked out. Renderers
+/// turn it into text, JSON, Markdown or HTML.
+#[derive(Clone, Debug, Serialize)]
+pub struct Transcript {
+    pub student: Student,
+    pub terms: Vec<TranscriptTerm>,
+    /// The totals of every semester added together.
+    pub totals: Totals,
+}
+
+/// One semester on a transcript along with the courses taken during it.
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptTerm {
+    pub semester: Semester,
+    pub courses: Vec<TranscriptCourse>,
+    pub totals: Totals,
+}
+
+/// One course on a transcript along with the grade received and how it counts.
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptCourse {
+    pub course: Course,
+    pub grade: Grade,
+    /// The course's credits times its grade's value, or None when the grade is not part of the GPA.
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub quality_points: Option<Decimal>,
+    pub attempt: Attempt,
+}
+
+/// The hours and GPA of a semester or a whole transcript. Each grade decides which of the hours a
+/// course's credits are added to.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Totals {
+    pub hours_attempted: u32,
+    pub hours_completed: u32,
+    pub gpa_hours: u32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub quality_points: Decimal,
+    /// Rounded or truncated by the GPA rule, or None when there are no GPA hours.
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub gpa: Option<Decimal>,
+}
+
+impl Totals {
+    fn add(&mut self, row: &TranscriptRow, attempt: Attempt) {
+        let (value, earns_credit) = match attempt {
+            Attempt::Only => (row.grade.value, row.grade.earns_credit),
+            Attempt::Counted { value, earns_credit } => (value, earns_credit),
+            // Attempts replaced by another one do not count toward anything
+            Attempt::Excluded => return,
+        };
+        let credits = u32::from(row.course.credits);
+        if row.grade.attempted {
+            self.hours_attempted += credits;
+        }
+        if earns_credit {
+            self.hours_completed += credits;
+        }
+        if row.grade.counts_toward_gpa {
+            self.gpa_hours += credits;
+            self.quality_points += Decimal::from(row.course.credits) * value;
+        }
+    }
+
+    fn add_totals(&mut self, other: &Totals) {
+        self.hours_attempted += other.hours_attempted;
+        self.hours_completed += other.hours_completed;
+        self.gpa_hours += other.gpa_hours;
+        self.quality_points += other.quality_points;
+    }
+}
+
+impl Transcript {
+    /// Loads the student's transcript. Repeated courses count the way the repeat policy says and the
+    /// GPAs are cut down by the GPA rule. The transcript has no terms if the student has not taken
+    /// any courses yet.
+    pub fn load(store: &mut dyn RegistrarStore, student: Student, policy: RepeatPolicy, rule: GpaRule) -> Result<Transcript> {
+        // Rows come back in semester order so each semester's courses are next to each other
+        let rows = store.transcript(&student)?;
+        let attempts = policy.apply(&rows);
+        let mut terms: Vec<TranscriptTerm> = Vec::new();
+        for (row, attempt) in rows.into_iter().zip(attempts) {
+            // Start a new term whenever a new semester starts
+            if !matches!(terms.last(), Some(term) if term.semester.code == row.semester.code) {
+                terms.push(TranscriptTerm {
+                    semester: row.semester.clone(),
+                    courses: Vec::new(),
+                    totals: Totals::default(),
+                });
+            }
+            let term = terms.len() - 1;
+            terms[term].totals.add(&row, attempt);
+            // Each course is worth its grade's value once for every credit, but only if the grade is part of the GPA
+            let quality_points = if row.grade.counts_toward_gpa {
+                Some(Decimal::from(row.course.credits) * row.grade.value)
+            } else {
+                None
+            };
+            terms[term].courses.push(TranscriptCourse {
+                course: row.course,
+                grade: row.grade,
+                quality_points,
+                attempt,
+            });
+        }
+        let mut totals = Totals::default();
+        for term in terms.iter_mut() {
+            totals.add_totals(&term.totals);
+            term.totals.gpa = rule.gpa(term.totals.quality_points, term.totals.gpa_hours);
+        }
+        totals.gpa = rule.gpa(totals.quality_points, totals.gpa_hours);
+        Ok(Transcript { student, terms, totals })
+    }
+
+    /// Returns every course on the transcript as its own line, in the same order.
+    pub fn lines(&self) -> Vec<TranscriptLine> {
+        let mut lines = Vec::new();
+        for term in self.terms.iter() {
+            for item in term.courses.iter() {
+                lines.push(TranscriptLine {
+                    code: term.semester.code.clone(),
+                    year: term.semester.year,
+                    description: term.semester.description.clone(),
+                    prefix: item.course.prefix.clone(),
+                    number: item.course.number.clone(),
+                    title: item.course.title.clone(),
+                    credits: item.course.credits,
+                    grade: item.grade.letter.clone(),
+                    quality_points: item.quality_points,
+                    repeat: repeat_note(item),
+                });
+            }
+        }
+        lines
+    }
+}
+
+/// One course on a transcript flattened into a single row along with its semester, for writing a
+/// transcript as a table, CSV, TSV or JSON Lines.
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptLine {
+    pub code: String,
+    pub year: u16,
+    pub description: String,
+    pub prefix: String,
+    pub number: String,
+    pub title: String,
+    pub credits: u8,
+    pub grade: String,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub quality_points: Option<Decimal>,
+    /// How the course counts if it was repeated.
+    pub repeat: Option<String>,
+}
+
+impl Record for TranscriptLine {
+    const COLUMNS: &'static [Column] = &[
+        Column::left("Code", 4),
+        Column::right("Year", 4),
+        Column::left("Desc", 6),
+        Column::left("Prefix", 6),
+        Column::right("Number", 6),
+        Column::left("Title", 32),
+        Column::right("Credits", 7),
+        Column::left("Type", 4),
+        Column::right("Quality Points", 14),
+        Column::left("Repeat", 31),
+    ];
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.year.to_string(),
+            self.description.clone(),
+            self.prefix.clone(),
+            self.number.clone(),
+            self.title.clone(),
+            self.credits.to_string(),
+            self.grade.clone(),
+            format_quality_points(self.quality_points),
+            self.repeat.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// How GPAs are cut down to a number of decimal places.
+#[derive(Clone, Copy, Debug)]
+pub struct GpaRule {
+    pub places: u32,
+    /// Drop the extra digits instead of rounding half away from zero.
+    pub truncate: bool,
+}
+
+impl Default for GpaRule {
+    fn default() -> GpaRule {
+        GpaRule { places: 2, truncate: false }
+    }
+}
+
+impl GpaRule {
+    /// Works out the GPA for the given quality points and hours, or None when there are no hours.
+    pub fn gpa(&self, quality_points: Decimal, hours: u32) -> Option<Decimal> {
+        if hours == 0 {
+            return None;
+        }
+        let strategy = if self.truncate {
+            RoundingStrategy::ToZero
+        } else {
+            RoundingStrategy::MidpointAwayFromZero
+        };
+        let mut gpa = (quality_points / Decimal::from(hours)).round_dp_with_strategy(self.places, strategy);
+        // Keep the trailing zeros so every GPA shows the same number of places
+        gpa.rescale(self.places);
+        Some(gpa)
+    }
+}
+
+/// How a course taken more than once counts toward a transcript's totals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepeatPolicy {
+    /// Only the latest attempt counts.
+    Replace,
+    /// The latest attempt counts with the average grade value of every attempt.
+    Average,
+    /// Only the attempt with the highest grade value counts toward the GPA. Credit is earned if any of
+    /// the attempts earned it.
+    Highest,
+}
+
+/// How one course on a transcript counts once the repeat policy has been applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Attempt {
+    /// The course was only taken once.
+    Only,
+    /// The course was repeated and this attempt counts with the given grade value, earning credit if
+    /// the policy says it does.
+    Counted {
+        #[serde(with = "rust_decimal::serde::float")]
+        value: Decimal,
+        earns_credit: bool,
+    },
+    /// The course was repeated and this attempt does not count.
+    Excluded,
+}
+
+impl RepeatPolicy {
+    /// Decides how each of the transcript rows counts. The rows have to be in semester order so the
+    /// latest attempt of a course comes last. Only attempts with a grade that counts toward the GPA
+    /// or earns credit are repeats, so grades like W or AU never replace an earlier attempt.
+    pub fn apply(self, rows: &[TranscriptRow]) -> Vec<Attempt> {
+        let mut attempts = vec![Attempt::Only; rows.len()];
+        let mut courses: Vec<((&str, &str), Vec<usize>)> = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            if !(row.grade.counts_toward_gpa || row.grade.earns_credit) {
+                continue;
+            }
+            let key = (row.course.prefix.as_str(), row.course.number.as_str());
+            match courses.iter_mut().find(|(course, _)| *course == key) {
+                Some((_, indexes)) => indexes.push(index),
+                None => courses.push((key, vec![index])),
+            }
+        }
+        for (_, indexes) in courses.iter().filter(|(_, indexes)| indexes.len() > 1) {
+            let latest = indexes[indexes.len() - 1];
+            let graded: Vec<usize> = indexes.iter().copied().filter(|index| rows[*index].grade.counts_toward_gpa).collect();
+            let kept = match self {
+                // Ties go to the latest attempt since max_by keeps the last of equal items
+                RepeatPolicy::Highest => graded
+                    .iter()
+                    .copied()
+                    .max_by(|a, b| rows[*a].grade.value.cmp(&rows[*b].grade.value))
+                    .unwrap_or(latest),
+                RepeatPolicy::Replace | RepeatPolicy::Average => latest,
+            };
+            // Credit is earned once if any of the attempts earned it
+            let earns_credit = indexes.iter().any(|index| rows[*index].grade.earns_credit);
+            let counted = match self {
+                RepeatPolicy::Average if rows[kept].grade.counts_toward_gpa => Attempt::Counted {
+                    value: graded.iter().map(|index| rows[*index].grade.value).sum::<Decimal>() / Decimal::from(graded.len()),
+                    earns_credit,
+                },
+                // A later credit only grade like P keeps its credit even though an earlier F is the
+                // attempt that counts toward the GPA
+                RepeatPolicy::Highest => Attempt::Counted {
+                    value: rows[kept].grade.value,
+                    earns_credit,
+                },
+                RepeatPolicy::Replace | RepeatPolicy::Average => Attempt::Counted {
+                    value: rows[kept].grade.value,
+                    earns_credit: rows[kept].grade.earns_credit,
+                },
+            };
+            for index in indexes {
+                attempts[*index] = if *index == kept { counted } else { Attempt::Excluded };
+            }
+        }
+        attempts
+    }
+}
+
+// Start render functions
+/// Turns a transcript into one kind of output. Other kinds of output can be added by implementing it.
+pub trait TranscriptRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String>;
+}
+
+/// The plain text transcript shown at the prompt.
+pub struct TextRenderer;
+
+/// The whole transcript as a JSON object.
+pub struct JsonRenderer;
+
+/// A Markdown document with a table for each semester.
+pub struct MarkdownRenderer;
+
+/// An HTML page with a table for each semester.
+pub struct HtmlRenderer;
+
+/// Every course as a line of a table, CSV, TSV or JSON Lines, leaving out the totals.
+pub struct LinesRenderer(pub Format);
+
+/// Names of the renderers that renderer() knows.
+pub const RENDERERS: [&str; 8] = ["text", "table", "csv", "tsv", "json", "jsonl", "markdown", "html"];
+
+/// Returns the renderer with the given name, which is one of RENDERERS or md for Markdown.
+pub fn renderer(name: &str) -> Option<Box<dyn TranscriptRenderer>> {
+    match name {
+        "text" => Some(Box::new(TextRenderer)),
+        "json" => Some(Box::new(JsonRenderer)),
+        "markdown" | "md" => Some(Box::new(MarkdownRenderer)),
+        "html" => Some(Box::new(HtmlRenderer)),
+        _ => Format::from_name(name).map(|format| Box::new(LinesRenderer(format)) as Box<dyn TranscriptRenderer>),
+    }
+}
+
+impl TranscriptRenderer for TextRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String> {
+        let mut output = String::new();
+        for term in transcript.terms.iter() {
+            output.push_str(&format!(
+                "============ Semester: {:<6} {} ============\n",
+                term.semester.description, term.semester.year
+            ));
+            for item in term.courses.iter() {
+                let mut line = format!(
+                    "{}{} {} ({}) {}",
+                    item.course.prefix, item.course.number, item.course.title, item.course.credits, item.grade.letter
+                );
+                if let Some(quality_points) = item.quality_points {
+                    line.push_str(&format!(" {:.2}", quality_points));
+                }
+                // Mark repeated courses with how they count
+                if let Some(note) = repeat_note(item) {
+                    line.push_str(&format!(" [{note}]"));
+                }
+                output.push_str(&format!("{line}\n"));
+            }
+            output.push_str(&format!(
+                "  TERM HOURS: {}  TERM ATTEMPTED: {}  TERM GPA: {}\n",
+                term.totals.hours_completed,
+                term.totals.hours_attempted,
+                format_gpa(term.totals.gpa)
+            ));
+        }
+        let totals = &transcript.totals;
+        output.push_str(&format!("  STUDENT HOURS ATTEMPTED: {}\n", totals.hours_attempted));
+        output.push_str(&format!("  STUDENT HOURS COMPLETED: {}\n", totals.hours_completed));
+        output.push_str(&format!("  STUDENT GPA HOURS: {}\n", totals.gpa_hours));
+        output.push_str(&format!("  STUDENT QUALITY POINTS: {:.2}\n", totals.quality_points));
+        output.push_str(&format!("  STUDENT GPA: {}\n", format_gpa(totals.gpa)));
+        Ok(output)
+    }
+}
+
+impl TranscriptRenderer for JsonRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String> {
+        match serde_json::to_string_pretty(transcript) {
+            Ok(json) => Ok(format!("{json}\n")),
+            Err(error) => Err(RegistrarError::Internal(format!("Unable to write transcript as JSON. {error}"))),
+        }
+    }
+}
+
+impl TranscriptRenderer for MarkdownRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String> {
+        let student = &transcript.student;
+        let mut output = format!(
+            "# Transcript for {} {} (#{})\n",
+            markdown_escape(&student.fname),
+            markdown_escape(&student.lname),
+            student.id
+        );
+        for term in transcript.terms.iter() {
+            output.push_str(&format!(
+                "\n## {} {}\n\n",
+                markdown_escape(&term.semester.description),
+                term.semester.year
+            ));
+            output.push_str("| Course | Title | Credits | Grade | Quality Points | Repeat |\n");
+            output.push_str("| --- | --- | ---: | --- | ---: | --- |\n");
+            for item in term.courses.iter() {
+                output.push_str(&format!(
+                    "| {}{} | {} | {} | {} | {} | {} |\n",
+                    markdown_escape(&item.course.prefix),
+                    markdown_escape(&item.course.number),
+                    markdown_escape(&item.course.title),
+                    item.course.credits,
+                    markdown_escape(&item.grade.letter),
+                    format_quality_points(item.quality_points),
+                    repeat_note(item).unwrap_or_default()
+                ));
+            }
+            output.push_str(&format!(
+                "\nTerm hours: {}, attempted: {}, GPA: {}\n",
+                term.totals.hours_completed,
+                term.totals.hours_attempted,
+                format_gpa(term.totals.gpa)
+            ));
+        }
+        let totals = &transcript.totals;
+        output.push_str("\n## Totals\n\n");
+        output.push_str("| Hours Attempted | Hours Completed | GPA Hours | Quality Points | GPA |\n");
+        output.push_str("| ---: | ---: | ---: | ---: | ---: |\n");
+        output.push_str(&format!(
+            "| {} | {} | {} | {:.2} | {} |\n",
+            totals.hours_attempted,
+            totals.hours_completed,
+            totals.gpa_hours,
+            totals.quality_points,
+            format_gpa(totals.gpa)
+        ));
+        Ok(output)
+    }
+}
+
+impl TranscriptRenderer for HtmlRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String> {
+        let student = &transcript.student;
+        let name = html_escape(&format!("{} {}", student.fname, student.lname));
+        let mut output = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format!("<title>Transcript for {name}</title>\n</head>\n<body>\n"));
+        output.push_str(&format!("<h1>Transcript for {name} (#{})</h1>\n", student.id));
+        for term in transcript.terms.iter() {
+            output.push_str(&format!(
+                "<h2>{} {}</h2>\n",
+                html_escape(&term.semester.description),
+                term.semester.year
+            ));
+            output.push_str("<table>\n<thead>\n");
+            output.push_str("<tr><th>Course</th><th>Title</th><th>Credits</th><th>Grade</th><th>Quality Points</th><th>Repeat</th></tr>\n");
+            output.push_str("</thead>\n<tbody>\n");
+            for item in term.courses.iter() {
+                output.push_str(&format!(
+                    "<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&item.course.prefix),
+                    html_escape(&item.course.number),
+                    html_escape(&item.course.title),
+                    item.course.credits,
+                    html_escape(&item.grade.letter),
+                    format_quality_points(item.quality_points),
+                    repeat_note(item).unwrap_or_default()
+                ));
+            }
+            output.push_str("</tbody>\n</table>\n");
+            output.push_str(&format!(
+                "<p>Term hours: {}, attempted: {}, GPA: {}</p>\n",
+                term.totals.hours_completed,
+                term.totals.hours_attempted,
+                format_gpa(term.totals.gpa)
+            ));
+        }
+        let totals = &transcript.totals;
+        output.push_str("<h2>Totals</h2>\n<table>\n<thead>\n");
+        output.push_str("<tr><th>Hours Attempted</th><th>Hours Completed</th><th>GPA Hours</th><th>Quality Points</th><th>GPA</th></tr>\n");
+        output.push_str("</thead>\n<tbody>\n");
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td></tr>\n",
+            totals.hours_attempted,
+            totals.hours_completed,
+            totals.gpa_hours,
+            totals.quality_points,
+            format_gpa(totals.gpa)
+        ));
+        output.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        Ok(output)
+    }
+}
+
+impl TranscriptRenderer for LinesRenderer {
+    fn render(&self, transcript: &Transcript) -> Result<String> {
+        output::render(&transcript.lines(), self.0)
+    }
+}
+
+/// Says how a repeated course counts, or None if the course was only taken once.
+fn repeat_note(item: &TranscriptCourse) -> Option<String> {
+    match item.attempt {
+        Attempt::Only => None,
+        Attempt::Counted { value, .. } if value != item.grade.value => {
+            Some(format!("repeat, counted as average {:.2}", value))
+        }
+        Attempt::Counted { .. } => Some(String::from("repeat, counted")),
+        Attempt::Excluded => Some(String::from("repeat, not counted")),
+    }
+}
+
+fn format_gpa(gpa: Option<Decimal>) -> String {
+    match gpa {
+        Some(gpa) => gpa.to_string(),
+        None => String::from("N/A"),
+    }
+}
+
+fn format_quality_points(quality_points: Option<Decimal>) -> String {
+    match quality_points {
+        Some(quality_points) => format!("{:.2}", quality_points),
+        None => String::new(),
+    }
+}
+
+/// Escapes the characters that Markdown would otherwise treat as formatting or a table cell border.
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+// End render functions
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(prefix: &str, letter: &str, value: i64, counts_toward_gpa: bool, earns_credit: bool) -> TranscriptRow {
+        TranscriptRow {
+            semester: Semester {
+                code: String::from("F23"),
+                year: 2023,
+                description: String::from("Fall"),
+                start_date: None,
+                end_date: None,
+            },
+            course: Course {
+                prefix: String::from(prefix),
+                number: String::from("101"),
+                title: String::from("Intro"),
+                credits: 3,
+            },
+            grade: Grade {
+                letter: String::from(letter),
+                value: Decimal::from(value),
+                counts_toward_gpa,
+                earns_credit,
+                attempted: true,
+            },
+        }
+    }
+
+    fn a() -> TranscriptRow {
+        row("CS", "A", 4, true, true)
+    }
+
+    fn c() -> TranscriptRow {
+        row("CS", "C", 2, true, true)
+    }
+
+    fn f() -> TranscriptRow {
+        row("CS", "F", 0, true, false)
+    }
+
+    fn p() -> TranscriptRow {
+        row("CS", "P", 0, false, true)
+    }
+
+    fn w() -> TranscriptRow {
+        row("CS", "W", 0, false, false)
+    }
+
+    fn counted(value: i64, earns_credit: bool) -> Attempt {
+        Attempt::Counted {
+            value: Decimal::from(value),
+            earns_credit,
+        }
+    }
+
+    fn totals(rows: &[TranscriptRow], policy: RepeatPolicy) -> Totals {
+        let mut totals = Totals::default();
+        for (row, attempt) in rows.iter().zip(policy.apply(rows)) {
+            totals.add(row, attempt);
+        }
+        totals
+    }
+
+    #[test]
+    fn courses_taken_once_are_left_alone() {
+        let rows = [a(), row("MA", "C", 2, true, true)];
+        assert_eq!(RepeatPolicy::Replace.apply(&rows), vec![Attempt::Only, Attempt::Only]);
+    }
+
+    #[test]
+    fn replace_counts_the_latest_attempt() {
+        let rows = [a(), c()];
+        assert_eq!(RepeatPolicy::Replace.apply(&rows), vec![Attempt::Excluded, counted(2, true)]);
+    }
+
+    #[test]
+    fn highest_counts_the_best_attempt() {
+        let rows = [c(), a(), c()];
+        assert_eq!(
+            RepeatPolicy::Highest.apply(&rows),
+            vec![Attempt::Excluded, counted(4, true), Attempt::Excluded]
+        );
+    }
+
+    #[test]
+    fn average_counts_the_latest_attempt_with_the_average_value() {
+        let rows = [f(), a()];
+        assert_eq!(RepeatPolicy::Average.apply(&rows), vec![Attempt::Excluded, counted(2, true)]);
+    }
+
+    #[test]
+    fn highest_keeps_credit_from_a_later_pass() {
+        let rows = [f(), p()];
+        assert_eq!(RepeatPolicy::Highest.apply(&rows), vec![counted(0, true), Attempt::Excluded]);
+        let totals = totals(&rows, RepeatPolicy::Highest);
+        assert_eq!(totals.hours_completed, 3);
+        assert_eq!(totals.gpa_hours, 3);
+    }
+
+    #[test]
+    fn withdrawals_are_not_repeats() {
+        let rows = [a(), w()];
+        assert_eq!(RepeatPolicy::Replace.apply(&rows), vec![Attempt::Only, Attempt::Only]);
+    }
+
+    #[test]
+    fn gpa_rounds_half_away_from_zero() {
+        let rule = GpaRule::default();
+        // 11 / 3 = 3.666...
+        assert_eq!(rule.gpa(Decimal::from(11), 3), Some(Decimal::new(367, 2)));
+        // 10.005 / 1 rounds up at the midpoint
+        assert_eq!(rule.gpa(Decimal::new(10005, 3), 1), Some(Decimal::new(1001, 2)));
+    }
+
+    #[test]
+    fn gpa_truncates_when_asked() {
+        let rule = GpaRule { places: 2, truncate: true };
+        assert_eq!(rule.gpa(Decimal::from(11), 3), Some(Decimal::new(366, 2)));
+        let rule = GpaRule { places: 0, truncate: true };
+        assert_eq!(rule.gpa(Decimal::from(11), 3), Some(Decimal::from(3)));
+    }
+
+    #[test]
+    fn gpa_keeps_trailing_zeros() {
+        let rule = GpaRule { places: 3, truncate: false };
+        assert_eq!(rule.gpa(Decimal::from(12), 3).unwrap().to_string(), "4.000");
+    }
+
+    #[test]
+    fn gpa_without_hours_is_none() {
+        assert_eq!(GpaRule::default().gpa(Decimal::ZERO, 0), None);
+    }
+}